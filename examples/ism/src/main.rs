@@ -36,7 +36,7 @@ fn main() -> Result<(), Box<dyn Error>> {
         .lstm(12, 1, Activation::Sigmoid);
        
     // evolve the solver to fit the problem
-    let (mut solution, _) = Population::<Neat, NeatEnvironment, ISM>::new()
+    let mut solution = Population::<Neat, NeatEnvironment, ISM>::new()
         .constrain(neat_env)
         .size(50)
         .populate_clone(net)
@@ -52,7 +52,7 @@ fn main() -> Result<(), Box<dyn Error>> {
         .run(|_, fit, num| {
             println!("Generation: {} score: {}", num, fit);
             num == num_evolve
-        })?;
+        })?.champion;
             
     // traditional training of neural networks
     solution.train(&ism.inputs, &ism.answers, 0.00001, Loss::Diff, |epoch, loss| {
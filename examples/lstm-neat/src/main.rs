@@ -34,7 +34,7 @@ fn main() -> Result<(), Box<dyn Error>> {
         .dense_pool(1, Activation::Sigmoid);
         // .lstm(10, 1, Activation::Sigmoid);
 
-    let (mut solution, _) = Population::<Neat, NeatEnvironment, MemoryTest>::new()
+    let mut solution = Population::<Neat, NeatEnvironment, MemoryTest>::new()
         .constrain(neat_env)
         .size(200)
         .populate_clone(starting_net)
@@ -50,7 +50,7 @@ fn main() -> Result<(), Box<dyn Error>> {
         .run(|_, fit, num| {
             println!("Generation: {} score: {}", num, fit);
             num == num_evolve
-        })?;
+        })?.champion;
         
     //let num_train = 0;
         // solution.train(&data.input, &data.output, 0.01, Loss::Diff, |iter, loss| {
@@ -431,7 +431,7 @@ impl Simulation {
         match job.task {
             SimTaskType::CalFitness => {
                 if let Some(idx) = work.member_idx {
-                    job.member = self.member(idx).map(|cont| cont.member.read().unwrap().clone());
+                    job.member = self.member(idx).map(|cont| cont.member.clone());
                 }
             },
             SimTaskType::TrainBest => {
@@ -31,7 +31,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     let xor = XOR::new();
 
 
-    let (mut solution, _) = Population::<Neat, NeatEnvironment, XOR>::new()
+    let mut solution = Population::<Neat, NeatEnvironment, XOR>::new()
         .constrain(neat_env)
         .size(200)
         .populate_clone(starting_net)
@@ -50,7 +50,7 @@ fn main() -> Result<(), Box<dyn Error>> {
             println!("Generation: {} score: {}", num, fit);
             let diff = 4.0 - fit;
             (diff > 0.0 && diff < 0.01) || num == num_evolve
-        })?;
+        })?.champion;
         
     println!("{:#?}", solution);
     xor.show(&mut solution);
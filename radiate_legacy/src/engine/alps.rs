@@ -0,0 +1,104 @@
+//! Age-layered population structure (ALPS). The population is split into layers by age,
+//! each layer only holds members younger than its maximum age and members only compete
+//! with those in their own layer and the layer below. The bottom layer is regularly filled
+//! with brand new random members so there is always fresh genetic material working its way up.
+
+
+
+/// How the maximum age of each layer grows, the maximum age of a layer is
+/// the age gap multiplied by the scheme's value for the layer
+///
+/// Linear - 1, 2, 3, 4, 5...
+/// Polynomial - 1, 2, 4, 9, 16...
+/// Exponential - 1, 2, 4, 8, 16...
+/// Fibonacci - 1, 2, 3, 5, 8...
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AgingScheme {
+    Linear,
+    Polynomial,
+    Exponential,
+    Fibonacci
+}
+
+
+
+/// Settings for running a population as age layers
+///
+/// layers: the number of age layers, the population size is the size of each layer
+/// age_gap: how many generations between new random members being put into the bottom layer
+/// aging: how the maximum age of each layer grows as the layers go up
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alps {
+    pub layers: usize,
+    pub age_gap: usize,
+    pub aging: AgingScheme
+}
+
+
+
+impl Alps {
+
+    pub fn new() -> Self {
+        Alps {
+            layers: 5,
+            age_gap: 10,
+            aging: AgingScheme::Polynomial
+        }
+    }
+
+
+    pub fn layers(mut self, layers: usize) -> Self {
+        self.layers = layers.max(1);
+        self
+    }
+
+
+    pub fn age_gap(mut self, age_gap: usize) -> Self {
+        self.age_gap = age_gap.max(1);
+        self
+    }
+
+
+    pub fn aging(mut self, aging: AgingScheme) -> Self {
+        self.aging = aging;
+        self
+    }
+
+
+    /// The oldest a member can be and still stay in a layer, the top
+    /// layer has no maximum age so it returns None
+    pub fn max_age(&self, layer: usize) -> Option<usize> {
+        if layer + 1 >= self.layers {
+            return None;
+        }
+        let scale = match self.aging {
+            AgingScheme::Linear => layer + 1,
+            AgingScheme::Polynomial => if layer < 2 { layer + 1 } else { layer * layer },
+            AgingScheme::Exponential => 1 << layer,
+            AgingScheme::Fibonacci => {
+                let (mut one, mut two) = (1, 2);
+                for _ in 0..layer {
+                    let next = one + two;
+                    one = two;
+                    two = next;
+                }
+                one
+            }
+        };
+        Some(self.age_gap * scale)
+    }
+
+
+    /// true if the bottom layer should be replaced by new random members after the given generation
+    pub fn inject(&self, generation: usize) -> bool {
+        generation > 0 && generation % self.age_gap.max(1) == 0
+    }
+}
+
+
+
+impl Default for Alps {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,546 @@
+//! Covariance Matrix Adaptation Evolution Strategy (CMA-ES) with IPOP restarts.
+//!
+//! This is an optimizer for continuous problems where the genome is just a vec of real numbers.
+//! Instead of crossing members over, a multivariate normal distribution is sampled each generation
+//! and the mean, step size, and covariance of that distribution are moved towards the best samples.
+//! When the search converges or stagnates, it is restarted with a larger population (IPOP) which
+//! makes the search more global with every restart.
+
+extern crate rayon;
+extern crate rand;
+
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::AtomicBool;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::time::Instant;
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::{StdRng, ThreadRng};
+use rayon::prelude::*;
+use super::{
+    problem::{Problem, EvalContext},
+    realvalued::RealValued,
+    report::{RunReport, StopReason},
+    stats::GenerationStats,
+    random
+};
+
+
+
+/// Cmaes is the driver for the strategy, much like the population it holds the
+/// problem to be solved and the settings for the search, then runs the search through
+/// a user defined function which decides when to stop
+pub struct Cmaes<T, P>
+    where
+        T: RealValued + Send + Sync,
+        P: Problem<T>
+{
+    dimension: usize,
+    start: Option<Vec<f32>>,
+    sigma: f32,
+    size: Option<usize>,
+    bounds: Option<(f32, f32)>,
+    restarts: usize,
+    increase: f32,
+    tolerance: f32,
+    stagnation: usize,
+    debug_progress: bool,
+    solve: Arc<RwLock<P>>,
+    phantom: PhantomData<T>
+}
+
+
+
+/// The internal state of a single CMA-ES search, this is thrown away and
+/// rebuilt with a bigger population every time the search is restarted.
+/// The math is done in f64 because the covariance matrix gets badly conditioned
+/// quickly in f32 as the search converges
+struct Strategy {
+    mean: Vec<f64>,
+    sigma: f64,
+    lambda: usize,
+    mu: usize,
+    weights: Vec<f64>,
+    mueff: f64,
+    cc: f64,
+    cs: f64,
+    c1: f64,
+    cmu: f64,
+    damps: f64,
+    chi_n: f64,
+    pc: Vec<f64>,
+    ps: Vec<f64>,
+    covariance: Vec<Vec<f64>>,
+    basis: Vec<Vec<f64>>,
+    scales: Vec<f64>,
+    generation: usize
+}
+
+
+
+/// implement the cmaes driver
+impl<T, P> Cmaes<T, P>
+    where
+        T: RealValued + Clone + Send + Sync,
+        P: Problem<T> + Send + Sync
+{
+
+    /// base cmaes settings
+    pub fn new() -> Self {
+        Cmaes {
+            // number of real values in each member, this must be set
+            dimension: 0,
+            // starting mean of the search, defaults to a random point within the bounds or the origin
+            start: None,
+            // initial step size of the search
+            sigma: 0.5,
+            // number of samples per generation, defaults to 4 + 3ln(n)
+            size: None,
+            // optional box constraints applied to every dimension
+            bounds: None,
+            // number of times the search is allowed to restart once it has converged
+            restarts: 0,
+            // how much the population grows with each restart
+            increase: 2.0,
+            // once the step size times the largest axis of the distribution falls below this, restart
+            tolerance: 1e-8,
+            // number of generations the best score can stay the same before restarting
+            stagnation: 50,
+            // print the state of the search to the screen
+            debug_progress: false,
+            // the problem to solve, shared between threads the same way the population does it
+            solve: Arc::new(RwLock::new(P::empty())),
+            phantom: PhantomData
+        }
+    }
+
+    /// Run the strategy according to a user defined function, the inputs of which are
+    /// the top member of the current generation, the fitness of that member, and the
+    /// current number of generations (counted through restarts).
+    /// This will continue until the function returns true or all the restarts have been
+    /// used up, either way the report holds the best member found during the whole search
+    /// re-scored on the full problem
+    pub fn run<F>(&mut self, runner: F) -> Result<RunReport<T, ()>, &'static str>
+        where F: Fn(&T, f32, i32) -> bool + Sized
+    {
+        if self.dimension == 0 {
+            return Err("Dimension not set");
+        }
+        if self.size.is_some_and(|size| size < 2) {
+            return Err("CMA-ES needs at least two samples a generation");
+        }
+        let start = Instant::now();
+        let mut r = rand::thread_rng();
+        let seed = r.gen::<u64>();
+        let mut lambda = self.size.unwrap_or(4 + (3.0 * (self.dimension as f32).ln()).floor() as usize);
+        let mut best: Option<(f32, T)> = None;
+        let mut history = Vec::new();
+        let mut evaluations = 0;
+        let mut index = 0;
+        for restart in 0..=self.restarts {
+            let mut strategy = Strategy::new(self.initial_mean(&mut r, restart), self.sigma as f64, lambda);
+            let mut recent = VecDeque::with_capacity(self.stagnation);
+            loop {
+                // sample the distribution and score every sample in parallel
+                let samples = strategy.sample(&mut r, self.bounds);
+                let (members, scores) = self.evaluate(&samples, seed, index as usize, evaluations);
+                evaluations += samples.len();
+                history.push(GenerationStats::from_scores(index as usize, 1, &scores));
+                let mut order = (0..samples.len()).collect::<Vec<_>>();
+                order.sort_by(|a, b| scores[*b].partial_cmp(&scores[*a]).unwrap());
+                // keep track of the best member over the entire search
+                let (top_score, top) = (scores[order[0]], &members[order[0]]);
+                if best.is_none() || top_score > best.as_ref().unwrap().0 {
+                    best = Some((top_score, top.clone()));
+                }
+                if self.debug_progress {
+                    println!("CMA-ES: restart( {} ) lambda( {} ) sigma( {:.5} ) score( {:.5} )", restart, lambda, strategy.sigma, top_score);
+                }
+                if runner(top, top_score, index) {
+                    return self.report(best, history, evaluations, start, StopReason::Satisfied);
+                }
+                index += 1;
+                // move the distribution and decide if this search is done
+                strategy.update(&samples, &order);
+                if strategy.converged(self.tolerance as f64) || self.stagnant(&mut recent, top_score) {
+                    break;
+                }
+            }
+            lambda = (lambda as f32 * self.increase).ceil() as usize;
+        }
+        self.report(best, history, evaluations, start, StopReason::Exhausted)
+    }
+
+    /// Keep the top scores of the last stagnation generations, the search is stagnant once the
+    /// range of those scores falls below the tolerance (TolFun)
+    fn stagnant(&self, recent: &mut VecDeque<f32>, top_score: f32) -> bool {
+        if self.stagnation == 0 {
+            return false;
+        }
+        if recent.len() == self.stagnation {
+            recent.pop_front();
+        }
+        recent.push_back(top_score);
+        let high = recent.iter().cloned().fold(f32::MIN, f32::max);
+        let low = recent.iter().cloned().fold(f32::MAX, f32::min);
+        recent.len() == self.stagnation && high - low < self.tolerance
+    }
+
+    /// evaluate the samples against the problem in parallel and return
+    /// the members created from the samples along with their scores. Each sample
+    /// is solved with a context seeded from the run's seed, the generation, and its index,
+    /// its id is the number of samples taken before it in the run
+    fn evaluate(&self, samples: &[Vec<f64>], seed: u64, generation: usize, taken: usize) -> (Vec<T>, Vec<f32>) {
+        let mut members = samples.iter()
+            .map(|x| T::from_values(x.iter().map(|v| *v as f32).collect()))
+            .collect::<Vec<_>>();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let scores = members.par_iter_mut()
+            .enumerate()
+            .map_with(Arc::clone(&self.solve), |problem, (index, member)| {
+                let mut context = EvalContext {
+                    generation,
+                    index,
+                    id: (taken + index) as u64,
+                    species: None,
+                    rng: StdRng::seed_from_u64(random::mix(seed, generation as u64, index as u64)),
+                    cancel: Arc::clone(&cancel)
+                };
+                problem.read().unwrap().solve_with_context(member, &mut context)
+            })
+            .collect::<Vec<_>>();
+        (members, scores)
+    }
+
+    /// build the report of the run, the best member is solved again on the full
+    /// problem so a problem which only solves part of its data each time reports the real fitness
+    fn report(&self, best: Option<(f32, T)>, history: Vec<GenerationStats>, evaluations: usize, start: Instant, stop: StopReason) -> Result<RunReport<T, ()>, &'static str> {
+        let (_, mut champion) = best.ok_or("Error Training")?;
+        let fitness = self.solve.read().unwrap().solve_full(&mut champion);
+        Ok(RunReport {
+            champion,
+            fitness,
+            environment: (),
+            history,
+            evaluations,
+            elapsed: start.elapsed(),
+            stop,
+            species: Vec::new()
+        })
+    }
+
+    /// the first search starts at the given start point if there is one, every restart
+    /// after that starts from a random point within the bounds to search somewhere new
+    fn initial_mean(&self, r: &mut ThreadRng, restart: usize) -> Vec<f64> {
+        match (&self.start, self.bounds) {
+            (Some(start), _) if restart == 0 => start.iter().map(|x| *x as f64).collect(),
+            (_, Some((lower, upper))) => (0..self.dimension).map(|_| r.gen_range(lower, upper) as f64).collect(),
+            (Some(start), None) => start.iter().map(|x| *x as f64 + random::gaussian(r) as f64).collect(),
+            (None, None) => vec![0.0; self.dimension]
+        }
+    }
+
+    /////////////////////////////////////////////////////////////////////////////////////////
+    //  configure the settings for the search, all have defaults except for the dimension  //
+    /////////////////////////////////////////////////////////////////////////////////////////
+
+    /// set the number of real values in each member
+    pub fn dimension(mut self, dimension: usize) -> Self {
+        self.dimension = dimension;
+        self
+    }
+
+    /// set the starting mean of the search, this also sets the dimension
+    pub fn start(mut self, start: Vec<f32>) -> Self {
+        self.dimension = start.len();
+        self.start = Some(start);
+        self
+    }
+
+    /// set the initial step size of the search, a good rule of thumb is
+    /// a third of the range the answer is expected to be in
+    pub fn sigma(mut self, sigma: f32) -> Self {
+        self.sigma = sigma;
+        self
+    }
+
+    /// set the number of samples to take each generation for the first search, this needs to be at least two
+    pub fn size(mut self, size: usize) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// clamp every value in a sample to be within (lower, upper)
+    pub fn bounds(mut self, lower: f32, upper: f32) -> Self {
+        self.bounds = Some((lower, upper));
+        self
+    }
+
+    /// turn on IPOP restarts - once a search converges it is restarted
+    /// with the population size multiplied by increase, up to restarts times
+    pub fn ipop(mut self, restarts: usize, increase: f32) -> Self {
+        self.restarts = restarts;
+        self.increase = increase;
+        self
+    }
+
+    /// set the tolerance at which a search is considered converged, both for the step size and
+    /// for the range of the top scores over the last stagnation generations
+    pub fn tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// set the number of generations the top score has to stay within the tolerance before a search
+    /// is considered stagnant, 0 turns this off
+    pub fn stagnation(mut self, stagnation: usize) -> Self {
+        self.stagnation = stagnation;
+        self
+    }
+
+    /// debug determines what to display to the screen during the search
+    pub fn debug(mut self, d: bool) -> Self {
+        self.debug_progress = d;
+        self
+    }
+
+    /// Impose a problem on the strategy, this is the same problem
+    /// that would be given to a population
+    pub fn impose(mut self, prob: P) -> Self {
+        self.solve = Arc::new(RwLock::new(prob));
+        self
+    }
+}
+
+
+
+impl<T, P> Default for Cmaes<T, P>
+    where
+        T: RealValued + Clone + Send + Sync,
+        P: Problem<T> + Send + Sync
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+
+/// implement the strategy following the default parameters from
+/// Hansen's "The CMA Evolution Strategy: A Tutorial"
+impl Strategy {
+
+    fn new(mean: Vec<f64>, sigma: f64, lambda: usize) -> Self {
+        let n = mean.len() as f64;
+        // at least one sample is recombined into the mean, otherwise the weights are 0 / 0
+        let mu = (lambda / 2).max(1);
+        // log weights for the top mu members, normalized to sum to one
+        let mut weights = (0..mu)
+            .map(|i| (mu as f64 + 0.5).ln() - (i as f64 + 1.0).ln())
+            .collect::<Vec<_>>();
+        let total = weights.iter().sum::<f64>();
+        weights.iter_mut().for_each(|w| *w /= total);
+        let mueff = 1.0 / weights.iter().map(|w| w * w).sum::<f64>();
+        // learning rates for the evolution paths and the covariance matrix
+        let cc = (4.0 + mueff / n) / (n + 4.0 + 2.0 * mueff / n);
+        let cs = (mueff + 2.0) / (n + mueff + 5.0);
+        let c1 = 2.0 / ((n + 1.3).powi(2) + mueff);
+        let cmu = (1.0 - c1).min(2.0 * (mueff - 2.0 + 1.0 / mueff) / ((n + 2.0).powi(2) + mueff));
+        let damps = 1.0 + 2.0 * (((mueff - 1.0) / (n + 1.0)).sqrt() - 1.0).max(0.0) + cs;
+        let chi_n = n.sqrt() * (1.0 - 1.0 / (4.0 * n) + 1.0 / (21.0 * n * n));
+        let dim = mean.len();
+        Strategy {
+            mean,
+            sigma,
+            lambda,
+            mu,
+            weights,
+            mueff,
+            cc,
+            cs,
+            c1,
+            cmu,
+            damps,
+            chi_n,
+            pc: vec![0.0; dim],
+            ps: vec![0.0; dim],
+            covariance: identity(dim),
+            basis: identity(dim),
+            scales: vec![1.0; dim],
+            generation: 0
+        }
+    }
+
+    /// sample lambda new points from N(mean, sigma^2 * C)
+    fn sample(&self, r: &mut ThreadRng, bounds: Option<(f32, f32)>) -> Vec<Vec<f64>> {
+        (0..self.lambda)
+            .map(|_| {
+                let z = self.scales.iter()
+                    .map(|d| d * random::gaussian(r) as f64)
+                    .collect::<Vec<_>>();
+                let y = multiply(&self.basis, &z);
+                self.mean.iter()
+                    .zip(y.iter())
+                    .map(|(m, y)| {
+                        let x = m + self.sigma * y;
+                        match bounds {
+                            Some((lower, upper)) => x.max(lower as f64).min(upper as f64),
+                            None => x
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// move the mean towards the best samples, then update the evolution paths,
+    /// covariance matrix, and step size. Order is the index of the samples from best to worst
+    fn update(&mut self, samples: &[Vec<f64>], order: &[usize]) {
+        let dim = self.mean.len();
+        let old_mean = self.mean.clone();
+        self.generation += 1;
+        // recombine the top mu samples into the new mean
+        self.mean = (0..dim)
+            .map(|j| (0..self.mu).map(|i| self.weights[i] * samples[order[i]][j]).sum())
+            .collect();
+        let step = self.mean.iter()
+            .zip(old_mean.iter())
+            .map(|(new, old)| (new - old) / self.sigma)
+            .collect::<Vec<_>>();
+        // update the conjugate evolution path with C^(-1/2) * step
+        let whitened = self.inverse_sqrt_multiply(&step);
+        let ps_rate = (self.cs * (2.0 - self.cs) * self.mueff).sqrt();
+        for (ps, w) in self.ps.iter_mut().zip(whitened.iter()) {
+            *ps = (1.0 - self.cs) * *ps + ps_rate * w;
+        }
+        let ps_norm = norm(&self.ps);
+        let hsig = ps_norm / (1.0 - (1.0 - self.cs).powi(2 * self.generation as i32)).sqrt() / self.chi_n < 1.4 + 2.0 / (dim as f64 + 1.0);
+        let hsig = if hsig { 1.0 } else { 0.0 };
+        // update the evolution path of the covariance matrix
+        let pc_rate = (self.cc * (2.0 - self.cc) * self.mueff).sqrt();
+        for (pc, s) in self.pc.iter_mut().zip(step.iter()) {
+            *pc = (1.0 - self.cc) * *pc + hsig * pc_rate * s;
+        }
+        // rank one and rank mu update of the covariance matrix
+        let deltas = order.iter()
+            .take(self.mu)
+            .map(|i| {
+                samples[*i].iter()
+                    .zip(old_mean.iter())
+                    .map(|(x, m)| (x - m) / self.sigma)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let decay = 1.0 - self.c1 - self.cmu;
+        let correction = (1.0 - hsig) * self.cc * (2.0 - self.cc);
+        for i in 0..dim {
+            for j in 0..=i {
+                let rank_one = self.pc[i] * self.pc[j] + correction * self.covariance[i][j];
+                let rank_mu = deltas.iter()
+                    .zip(self.weights.iter())
+                    .map(|(d, w)| w * d[i] * d[j])
+                    .sum::<f64>();
+                let value = decay * self.covariance[i][j] + self.c1 * rank_one + self.cmu * rank_mu;
+                self.covariance[i][j] = value;
+                self.covariance[j][i] = value;
+            }
+        }
+        // adapt the step size by comparing the length of the path to its expected length
+        self.sigma *= ((self.cs / self.damps) * (ps_norm / self.chi_n - 1.0)).exp();
+        // decompose the covariance matrix into C = B * D^2 * B^T
+        let (values, vectors) = eigen(&self.covariance);
+        self.scales = values.iter().map(|v| v.max(1e-20).sqrt()).collect();
+        self.basis = vectors;
+    }
+
+    /// compute C^(-1/2) * vec = B * D^-1 * B^T * vec
+    fn inverse_sqrt_multiply(&self, vec: &[f64]) -> Vec<f64> {
+        let dim = vec.len();
+        let projected = (0..dim)
+            .map(|j| (0..dim).map(|i| self.basis[i][j] * vec[i]).sum::<f64>() / self.scales[j])
+            .collect::<Vec<_>>();
+        multiply(&self.basis, &projected)
+    }
+
+    /// the search is done if the distribution has collapsed, the step size has
+    /// blown up, or the covariance matrix is too badly conditioned to keep going
+    fn converged(&self, tolerance: f64) -> bool {
+        let largest = self.scales.iter().cloned().fold(0.0, f64::max);
+        let smallest = self.scales.iter().cloned().fold(f64::MAX, f64::min);
+        !self.sigma.is_finite()
+            || self.sigma * largest < tolerance
+            || (largest / smallest).powi(2) > 1e14
+    }
+}
+
+
+
+/// identity matrix of size n
+fn identity(n: usize) -> Vec<Vec<f64>> {
+    (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect()
+}
+
+
+
+/// multiply a square matrix by a vector
+fn multiply(matrix: &[Vec<f64>], vec: &[f64]) -> Vec<f64> {
+    matrix.iter()
+        .map(|row| row.iter().zip(vec.iter()).map(|(a, b)| a * b).sum())
+        .collect()
+}
+
+
+
+/// euclidean length of a vector
+fn norm(vec: &[f64]) -> f64 {
+    vec.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+
+
+/// Eigen decomposition of a symmetric matrix using cyclic Jacobi rotations.
+/// Returns the eigenvalues and a matrix whose columns are the matching eigenvectors.
+/// Jacobi is slow for big matrices but it is simple and very accurate, which is
+/// what matters for the small dimensions CMA-ES is normally used for
+fn eigen(matrix: &[Vec<f64>]) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = matrix.len();
+    let mut a = matrix.to_vec();
+    let mut vectors = identity(n);
+    for _ in 0..100 {
+        // stop once everything off the diagonal is effectively zero
+        let off = (0..n)
+            .map(|i| (0..n).filter(|j| *j != i).map(|j| a[i][j] * a[i][j]).sum::<f64>())
+            .sum::<f64>();
+        if off < 1e-30 {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[p][q].abs() < 1e-300 {
+                    continue;
+                }
+                // find the rotation which zeros out a[p][q]
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                // rotate the columns then the rows of a, and accumulate the rotation in the eigenvectors
+                for row in a.iter_mut().chain(vectors.iter_mut()) {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = c * kp - s * kq;
+                    row[q] = s * kp + c * kq;
+                }
+                let (low, high) = a.split_at_mut(q);
+                for (pk, qk) in low[p].iter_mut().zip(high[0].iter_mut()) {
+                    let (one, two) = (*pk, *qk);
+                    *pk = c * one - s * two;
+                    *qk = s * one + c * two;
+                }
+            }
+        }
+    }
+    ((0..n).map(|i| a[i][i]).collect(), vectors)
+}
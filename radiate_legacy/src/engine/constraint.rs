@@ -0,0 +1,191 @@
+//! Constraint handling. A problem can report how much a member violates its constraints
+//! separately from its fitness, then before selection the fitness score of every member is
+//! replaced by a score which takes the violation into account. Survival and parental criteria
+//! only ever look at that score, so they pick members by the chosen constraint handling.
+
+extern crate rand;
+
+use rand::Rng;
+use super::{
+    genome::Genome,
+    generation::Container
+};
+
+
+
+/// How members which violate their constraints are handled
+///
+/// FeasibilityRules - a feasible member always beats an infeasible one, feasible members are compared
+///                    by fitness and infeasible members are compared by how much they violate their constraints
+/// AdaptivePenalty - subtract penalty times the violation from the fitness. If the best member has been feasible
+///                   for window generations in a row the penalty is multiplied by decrease, if it has been infeasible
+///                   for window generations in a row it is multiplied by increase
+/// StochasticRanking - rank the members with a stochastic bubble sort, two neighbors are compared by fitness if both are
+///                     feasible or with the given probability, otherwise by violation. Scores are given by rank
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Constraint {
+    FeasibilityRules,
+    AdaptivePenalty { penalty: f32, increase: f32, decrease: f32, window: usize },
+    StochasticRanking(f32)
+}
+
+
+
+/// The constraint handling of a population along with any state it needs to keep between generations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Constraints {
+    pub handling: Constraint,
+    pub penalty: f32,
+    history: Vec<bool>
+}
+
+
+
+impl Constraint {
+
+    /// adaptive penalty with common settings - start at 1, grow by 2x and shrink by half over 5 generations
+    pub fn adaptive_penalty() -> Self {
+        Constraint::AdaptivePenalty { penalty: 1.0, increase: 2.0, decrease: 0.5, window: 5 }
+    }
+}
+
+
+
+impl Constraints {
+
+    pub fn new(handling: Constraint) -> Self {
+        let penalty = match &handling {
+            Constraint::AdaptivePenalty { penalty, .. } => *penalty,
+            _ => 0.0
+        };
+        Constraints {
+            handling,
+            penalty,
+            history: Vec::new()
+        }
+    }
+
+    /// Replace the fitness score of every member of a generation with its score under the constraint handling,
+    /// then adapt the penalty to how the generation went. The fitness the member was given by the problem is
+    /// kept in its objective before it is overwritten
+    pub fn apply<T, E>(&mut self, members: &mut [Container<T, E>])
+        where
+            T: Genome<T, E> + Send + Sync,
+            E: Send + Sync
+    {
+        self.score(members);
+        self.adapt(members.iter());
+    }
+
+    /// Replace the fitness score of every member with its score under the constraint handling without adapting
+    /// the penalty. Age layers are scored one at a time and adapt once for the whole generation
+    pub fn score<T, E>(&self, members: &mut [Container<T, E>])
+        where
+            T: Genome<T, E> + Send + Sync,
+            E: Send + Sync
+    {
+        if members.is_empty() {
+            return;
+        }
+        for cont in members.iter_mut() {
+            cont.objective = cont.fitness_score;
+        }
+        match self.handling {
+            Constraint::FeasibilityRules => Constraints::feasibility_rules(members),
+            Constraint::AdaptivePenalty { .. } => self.adaptive_penalty(members),
+            Constraint::StochasticRanking(prob) => Constraints::stochastic_ranking(members, prob)
+        }
+    }
+
+    /// Adapt the penalty by whether the best scored member of the generation was feasible. If it has been
+    /// feasible for window generations in a row the penalty shrinks, if it has been infeasible it grows.
+    /// This is called once a generation after every member has been scored, and only changes adaptive penalty
+    pub fn adapt<'a, T, E, I>(&mut self, members: I)
+        where
+            T: Genome<T, E> + Send + Sync + 'a,
+            E: Send + Sync + 'a,
+            I: Iterator<Item = &'a Container<T, E>>
+    {
+        if let Constraint::AdaptivePenalty { increase, decrease, window, .. } = self.handling {
+            let best = match members.max_by(|a, b| a.fitness_score.partial_cmp(&b.fitness_score).unwrap()) {
+                Some(best) => best.is_feasible(),
+                None => return
+            };
+            self.history.push(best);
+            if self.history.len() >= window.max(1) {
+                if self.history.iter().all(|x| *x) {
+                    self.penalty *= decrease;
+                } else if self.history.iter().all(|x| !*x) {
+                    self.penalty *= increase;
+                }
+                self.history.clear();
+            }
+        }
+    }
+
+    /// Infeasible members score below the worst feasible member by the amount they violate their
+    /// constraints, so any ranking by score follows the feasibility rules
+    fn feasibility_rules<T, E>(members: &mut [Container<T, E>])
+        where
+            T: Genome<T, E> + Send + Sync,
+            E: Send + Sync
+    {
+        let worst_feasible = members.iter()
+            .filter(|x| x.is_feasible())
+            .map(|x| x.objective)
+            .fold(None, |worst: Option<f32>, curr| Some(worst.map_or(curr, |w| w.min(curr))));
+        let floor = worst_feasible.unwrap_or_else(|| members.iter().map(|x| x.objective).fold(f32::MAX, f32::min));
+        for cont in members.iter_mut() {
+            if !cont.is_feasible() {
+                cont.fitness_score = floor - cont.violation;
+            }
+        }
+    }
+
+    /// penalize the fitness by the violation
+    fn adaptive_penalty<T, E>(&self, members: &mut [Container<T, E>])
+        where
+            T: Genome<T, E> + Send + Sync,
+            E: Send + Sync
+    {
+        for cont in members.iter_mut() {
+            cont.fitness_score = cont.objective - self.penalty * cont.violation;
+        }
+    }
+
+    /// rank the members with a stochastic bubble sort, then spread the scores evenly
+    /// between the highest and lowest fitness by rank
+    fn stochastic_ranking<T, E>(members: &mut [Container<T, E>], prob: f32)
+        where
+            T: Genome<T, E> + Send + Sync,
+            E: Send + Sync
+    {
+        let mut r = rand::thread_rng();
+        let size = members.len();
+        let mut ranks = (0..size).collect::<Vec<_>>();
+        for _ in 0..size {
+            let mut swapped = false;
+            for j in 0..size - 1 {
+                let (one, two) = (&members[ranks[j]], &members[ranks[j + 1]]);
+                let swap = if (one.is_feasible() && two.is_feasible()) || r.gen::<f32>() < prob {
+                    one.objective < two.objective
+                } else {
+                    one.violation > two.violation
+                };
+                if swap {
+                    ranks.swap(j, j + 1);
+                    swapped = true;
+                }
+            }
+            if !swapped {
+                break;
+            }
+        }
+        let high = members.iter().map(|x| x.objective).fold(f32::MIN, f32::max);
+        let low = members.iter().map(|x| x.objective).fold(f32::MAX, f32::min);
+        let step = if size > 1 && high > low { (high - low) / (size - 1) as f32 } else { 1.0 };
+        for (rank, index) in ranks.into_iter().enumerate() {
+            members[index].fitness_score = high - step * rank as f32;
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! A problem backed by a dataset which only solves each member on a random mini-batch
+//! of the rows each generation. Every member in a generation sees the same batch so the
+//! scores are fair, and a new batch is drawn between generations. Datasets with millions
+//! of rows can't be run in full for every member every generation, this makes them possible.
+
+extern crate rand;
+
+use std::sync::Arc;
+use std::marker::PhantomData;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::index;
+use super::{
+    problem::Problem,
+    stats::GenerationStats
+};
+
+
+
+/// A scorer gives a member a score for a single row of the dataset, the
+/// member's fitness is the mean score over the rows it was solved on
+pub trait Scorer<T> {
+    fn score(&self, member: &mut T, input: &[f32], answer: &[f32]) -> f32;
+}
+
+
+
+/// A problem over a dataset of inputs and answers solved a mini-batch at a time.
+/// The data is held in an Arc so copies of the problem don't copy the data
+pub struct MiniBatch<T, S> {
+    inputs: Arc<Vec<Vec<f32>>>,
+    answers: Arc<Vec<Vec<f32>>>,
+    batch_size: usize,
+    batch: Vec<usize>,
+    rng: StdRng,
+    scorer: S,
+    phantom: PhantomData<T>
+}
+
+
+
+impl<T, S> MiniBatch<T, S>
+    where S: Scorer<T> + Default
+{
+
+    /// Create a mini-batch problem over a dataset, the first batch is drawn right away.
+    /// A batch size of at least the number of rows solves on the whole dataset every time
+    pub fn new(inputs: Vec<Vec<f32>>, answers: Vec<Vec<f32>>, batch_size: usize) -> Self {
+        let mut batch = MiniBatch {
+            inputs: Arc::new(inputs),
+            answers: Arc::new(answers),
+            batch_size,
+            batch: Vec::new(),
+            rng: StdRng::from_rng(rand::thread_rng()).unwrap(),
+            scorer: S::default(),
+            phantom: PhantomData
+        };
+        batch.resample();
+        batch
+    }
+
+    /// give the problem a scorer with settings instead of the default one
+    pub fn scorer(mut self, scorer: S) -> Self {
+        self.scorer = scorer;
+        self
+    }
+
+    /// seed the random number generator batches are drawn with, this draws a new first batch
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.resample();
+        self
+    }
+
+    /// draw a new random batch of rows without replacement
+    pub fn resample(&mut self) {
+        let rows = self.inputs.len().min(self.answers.len());
+        self.batch = if self.batch_size >= rows {
+            (0..rows).collect()
+        } else {
+            index::sample(&mut self.rng, rows, self.batch_size).into_vec()
+        };
+    }
+
+    /// the indexes of the rows in the current batch
+    pub fn batch(&self) -> &[usize] {
+        &self.batch
+    }
+
+    /// true if every batch is the whole dataset
+    pub fn is_full(&self) -> bool {
+        self.batch_size >= self.inputs.len().min(self.answers.len())
+    }
+
+    /// the mean score of the member over a set of rows
+    fn mean_score<I>(&self, member: &mut T, rows: I) -> f32
+        where I: Iterator<Item = usize>
+    {
+        let (mut total, mut count) = (0.0, 0);
+        for row in rows {
+            total += self.scorer.score(member, &self.inputs[row], &self.answers[row]);
+            count += 1;
+        }
+        total / count.max(1) as f32
+    }
+}
+
+
+
+impl<T, S> Problem<T> for MiniBatch<T, S>
+    where S: Scorer<T> + Default
+{
+
+    fn empty() -> Self {
+        MiniBatch::new(Vec::new(), Vec::new(), 0)
+    }
+
+    /// the mean score over the current batch
+    fn solve(&self, member: &mut T) -> f32 {
+        self.mean_score(member, self.batch.iter().cloned())
+    }
+
+    /// A new batch is drawn every generation. That is a new sample of the same problem, not a new problem,
+    /// so it never reports a change - stagnation, the surrogate and the hall of fame carry on as they were
+    fn advance(&mut self, _stats: &GenerationStats) -> bool {
+        if !self.is_full() {
+            self.resample();
+        }
+        false
+    }
+
+    /// the mean score over every row in the dataset
+    fn solve_full(&self, member: &mut T) -> f32 {
+        let rows = self.inputs.len().min(self.answers.len());
+        self.mean_score(member, 0..rows)
+    }
+}
+
+
+
+impl<T, S> Clone for MiniBatch<T, S>
+    where S: Clone
+{
+    fn clone(&self) -> Self {
+        MiniBatch {
+            inputs: Arc::clone(&self.inputs),
+            answers: Arc::clone(&self.answers),
+            batch_size: self.batch_size,
+            batch: self.batch.clone(),
+            rng: self.rng.clone(),
+            scorer: self.scorer.clone(),
+            phantom: PhantomData
+        }
+    }
+}
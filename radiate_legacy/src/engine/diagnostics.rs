@@ -0,0 +1,342 @@
+//! Diagnostics of the fitness landscape and of a run. When a run fails it can be the problem -
+//! a deceptive or flat landscape - or the settings, and these help tell the two apart. The
+//! landscape is sampled with the genome's base function and walked with its mutation, and every
+//! result is plain data which can be serialized and plotted.
+//!
+//! fitness distance correlation - how well fitness predicts the distance to the optimum, for a
+//!     problem which is maximized a strongly negative correlation is an easy landscape and a
+//!     positive one is deceptive
+//! random walk autocorrelation - how alike the fitness of members a number of mutations apart is,
+//!     a landscape with a long correlation length is smooth and one with a short one is rugged
+//! neutrality - the fraction of mutations which don't change the fitness at all, a landscape with
+//!     a lot of neutrality has plateaus a run can drift on without any signal
+//! diversity - the mean distance between every pair of members, tracked each generation to see
+//!     if the population collapsed onto one spot too early
+
+extern crate rayon;
+extern crate serde_json;
+
+use std::fs::File;
+use std::error::Error;
+use std::sync::{Arc, RwLock};
+use rayon::prelude::*;
+use super::{
+    genome::Genome,
+    problem::Problem,
+    environment::Envionment
+};
+
+
+
+/// How the landscape is sampled
+///
+/// samples: the number of random members used for the fitness distance correlation and neutrality
+/// walks: the number of random walks
+/// walk_length: the number of mutations in each walk
+/// max_lag: the largest number of steps apart the autocorrelation of a walk is measured at
+/// tolerance: the most the fitness can change for a mutation to still be neutral
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Landscape {
+    pub samples: usize,
+    pub walks: usize,
+    pub walk_length: usize,
+    pub max_lag: usize,
+    pub tolerance: f32
+}
+
+
+
+/// The fitness distance correlation of a set of samples
+///
+/// fitness: the fitness of every sample
+/// distance: the distance of every sample to the reference
+/// correlation: the pearson correlation between the two, 0 if either doesn't vary
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FitnessDistance {
+    pub fitness: Vec<f32>,
+    pub distance: Vec<f32>,
+    pub correlation: f32
+}
+
+
+
+/// A single random walk
+///
+/// fitness: the fitness at every step of the walk, starting with the random first member
+/// autocorrelation: the autocorrelation of the fitness at lags 1 through the max lag
+/// correlation_length: -1 / ln |autocorrelation at lag 1|, how many steps the fitness stays alike
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RandomWalk {
+    pub fitness: Vec<f32>,
+    pub autocorrelation: Vec<f32>,
+    pub correlation_length: f32
+}
+
+
+
+/// What single mutations do to the fitness
+///
+/// ratio: the fraction of mutations which didn't change the fitness
+/// better: the fraction of mutations which improved the fitness
+/// worse: the fraction of mutations which made the fitness worse
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Neutrality {
+    pub ratio: f32,
+    pub better: f32,
+    pub worse: f32
+}
+
+
+
+/// Every diagnostic of the landscape together, the autocorrelation is the mean over the walks
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LandscapeReport {
+    pub fitness_distance: FitnessDistance,
+    pub walks: Vec<RandomWalk>,
+    pub autocorrelation: Vec<f32>,
+    pub neutrality: Neutrality
+}
+
+
+
+impl Landscape {
+
+    /// 500 samples, 10 walks of 100 mutations, autocorrelation up to 10 steps apart, and only exactly equal fitness is neutral
+    pub fn new() -> Self {
+        Landscape {
+            samples: 500,
+            walks: 10,
+            walk_length: 100,
+            max_lag: 10,
+            tolerance: 0.0
+        }
+    }
+
+    /// set the number of random members sampled
+    pub fn samples(mut self, samples: usize) -> Self {
+        self.samples = samples.max(2);
+        self
+    }
+
+    /// set the number of walks and the number of mutations in each
+    pub fn walks(mut self, walks: usize, walk_length: usize) -> Self {
+        self.walks = walks;
+        self.walk_length = walk_length.max(1);
+        self
+    }
+
+    /// set the largest lag the autocorrelation is measured at
+    pub fn max_lag(mut self, max_lag: usize) -> Self {
+        self.max_lag = max_lag.max(1);
+        self
+    }
+
+    /// set how much the fitness can change for a mutation to still be neutral
+    pub fn tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance.abs();
+        self
+    }
+
+    /// Run every diagnostic of the landscape. The fitness distance correlation is measured against
+    /// the optimum if it is known, otherwise against the fittest sample
+    pub fn analyze<T, E, P>(&self, problem: &P, env: &Arc<RwLock<E>>, optimum: Option<&T>) -> LandscapeReport
+        where
+            T: Genome<T, E> + Clone + Send + Sync,
+            E: Envionment + Send + Sync,
+            P: Problem<T> + Sync
+    {
+        let walks = self.random_walks(problem, env);
+        let autocorrelation = (0..self.max_lag)
+            .map(|lag| {
+                let values = walks.iter().filter_map(|x| x.autocorrelation.get(lag)).collect::<Vec<_>>();
+                values.iter().cloned().sum::<f32>() / values.len().max(1) as f32
+            })
+            .collect();
+        LandscapeReport {
+            fitness_distance: self.fitness_distance(problem, env, optimum),
+            walks,
+            autocorrelation,
+            neutrality: self.neutrality(problem, env)
+        }
+    }
+
+    /// The correlation between the fitness of random members and their distance to the optimum,
+    /// or to the fittest of them if the optimum isn't known
+    pub fn fitness_distance<T, E, P>(&self, problem: &P, env: &Arc<RwLock<E>>, optimum: Option<&T>) -> FitnessDistance
+        where
+            T: Genome<T, E> + Clone + Send + Sync,
+            E: Envionment + Send + Sync,
+            P: Problem<T> + Sync
+    {
+        let (members, fitness) = Landscape::sample(self.samples, problem, env);
+        let fittest = fitness.iter()
+            .enumerate()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+            .map(|x| x.0);
+        let reference = match (optimum, fittest) {
+            (Some(optimum), _) => optimum,
+            (None, Some(index)) => &members[index],
+            (None, None) => return FitnessDistance::default()
+        };
+        let distance = members.par_iter()
+            .map(|member| T::distance(member, reference, Arc::clone(env)))
+            .collect::<Vec<_>>();
+        FitnessDistance {
+            correlation: correlation(&fitness, &distance),
+            fitness,
+            distance
+        }
+    }
+
+    /// Walk the landscape from random members by mutating them over and over, the walks are run in parallel
+    pub fn random_walks<T, E, P>(&self, problem: &P, env: &Arc<RwLock<E>>) -> Vec<RandomWalk>
+        where
+            T: Genome<T, E> + Clone + Send + Sync,
+            E: Envionment + Send + Sync,
+            P: Problem<T> + Sync
+    {
+        let (starts, _) = Landscape::sample(self.walks, problem, env);
+        starts.into_par_iter()
+            .map(|mut member| {
+                let mut fitness = vec![problem.solve(&mut member.clone())];
+                for _ in 0..self.walk_length {
+                    if let Some(next) = T::mutate(&member, Arc::clone(env)) {
+                        member = next;
+                    }
+                    fitness.push(problem.solve(&mut member.clone()));
+                }
+                let autocorrelation = (1..=self.max_lag).map(|lag| autocorrelation(&fitness, lag)).collect::<Vec<_>>();
+                let correlation_length = match autocorrelation.first().map(|x| x.abs()) {
+                    Some(first) if first >= 1.0 => f32::INFINITY,
+                    Some(first) if first > 0.0 => -1.0 / first.ln(),
+                    _ => 0.0
+                };
+                RandomWalk { fitness, autocorrelation, correlation_length }
+            })
+            .collect()
+    }
+
+    /// Mutate random members once each and count how often the fitness didn't change
+    pub fn neutrality<T, E, P>(&self, problem: &P, env: &Arc<RwLock<E>>) -> Neutrality
+        where
+            T: Genome<T, E> + Clone + Send + Sync,
+            E: Envionment + Send + Sync,
+            P: Problem<T> + Sync
+    {
+        let (members, fitness) = Landscape::sample(self.samples, problem, env);
+        let changes = members.into_par_iter()
+            .zip(fitness)
+            .filter_map(|(member, before)| {
+                let mut child = T::mutate(&member, Arc::clone(env))?;
+                Some(problem.solve(&mut child) - before)
+            })
+            .collect::<Vec<_>>();
+        if changes.is_empty() {
+            return Neutrality::default();
+        }
+        let total = changes.len() as f32;
+        let better = changes.iter().filter(|x| **x > self.tolerance).count() as f32;
+        let worse = changes.iter().filter(|x| **x < -self.tolerance).count() as f32;
+        Neutrality {
+            ratio: (total - better - worse) / total,
+            better: better / total,
+            worse: worse / total
+        }
+    }
+
+    /// random members from the genome's base function and their fitness
+    fn sample<T, E, P>(count: usize, problem: &P, env: &Arc<RwLock<E>>) -> (Vec<T>, Vec<f32>)
+        where
+            T: Genome<T, E> + Clone + Send + Sync,
+            E: Envionment + Send + Sync,
+            P: Problem<T> + Sync
+    {
+        let members = {
+            let mut lock_set = env.write().unwrap();
+            (0..count).map(|_| T::base(&mut lock_set)).collect::<Vec<_>>()
+        };
+        let fitness = members.par_iter()
+            .map(|member| problem.solve(&mut member.clone()))
+            .collect();
+        (members, fitness)
+    }
+}
+
+
+
+impl Default for Landscape {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+
+impl LandscapeReport {
+
+    /// save the report as json to a file path
+    pub fn save_json(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
+        serde_json::to_writer(&File::create(file_path)?, &self)?;
+        Ok(())
+    }
+}
+
+
+
+/// The mean distance between every pair of members, computed in parallel. This is n * (n - 1) / 2
+/// calls to Genome::distance, 0 if there are fewer than two members
+pub fn diversity<T, E>(members: &[&T], env: &Arc<RwLock<E>>) -> f32
+    where
+        T: Genome<T, E> + Send + Sync,
+        E: Send + Sync
+{
+    let size = members.len();
+    if size < 2 {
+        return 0.0;
+    }
+    let total = (0..size)
+        .into_par_iter()
+        .map(|i| (i + 1..size).map(|j| T::distance(members[i], members[j], Arc::clone(env))).sum::<f32>())
+        .sum::<f32>();
+    total / (size * (size - 1) / 2) as f32
+}
+
+
+
+/// the pearson correlation of two lists of values, 0 if either doesn't vary
+fn correlation(one: &[f32], two: &[f32]) -> f32 {
+    let size = one.len().min(two.len());
+    if size < 2 {
+        return 0.0;
+    }
+    let (mean_one, mean_two) = (one[..size].iter().sum::<f32>() / size as f32, two[..size].iter().sum::<f32>() / size as f32);
+    let (mut covariance, mut var_one, mut var_two) = (0.0, 0.0, 0.0);
+    for (a, b) in one.iter().zip(two.iter()) {
+        covariance += (a - mean_one) * (b - mean_two);
+        var_one += (a - mean_one).powi(2);
+        var_two += (b - mean_two).powi(2);
+    }
+    if var_one <= 0.0 || var_two <= 0.0 {
+        return 0.0;
+    }
+    covariance / (var_one * var_two).sqrt()
+}
+
+
+
+/// the autocorrelation of a series at a lag, 0 if the series doesn't vary or is too short
+fn autocorrelation(series: &[f32], lag: usize) -> f32 {
+    if series.len() <= lag {
+        return 0.0;
+    }
+    let mean = series.iter().sum::<f32>() / series.len() as f32;
+    let variance = series.iter().map(|x| (x - mean).powi(2)).sum::<f32>();
+    if variance <= 0.0 {
+        return 0.0;
+    }
+    let covariance = series.iter()
+        .zip(series.iter().skip(lag))
+        .map(|(a, b)| (a - mean) * (b - mean))
+        .sum::<f32>();
+    covariance / variance
+}
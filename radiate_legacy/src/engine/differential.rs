@@ -0,0 +1,244 @@
+//! Differential evolution using the classic DE/rand/1/bin scheme.
+//!
+//! Each member of the population is a vec of real numbers. Every generation, each member
+//! (the target) competes against a trial member which is built by adding the scaled difference
+//! of two random members to a third random member, then binomially crossing that vector over with
+//! the target. The trial only replaces the target if it scores at least as well.
+
+extern crate rayon;
+extern crate rand;
+
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::AtomicBool;
+use std::marker::PhantomData;
+use std::time::Instant;
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::{StdRng, ThreadRng};
+use rayon::prelude::*;
+use super::{
+    problem::{Problem, EvalContext},
+    realvalued::RealValued,
+    report::{RunReport, StopReason},
+    stats::GenerationStats,
+    random
+};
+
+
+
+/// DifferentialEvolution is the driver for the optimizer, it is set up and
+/// run the same way a population is
+pub struct DifferentialEvolution<T, P>
+    where
+        T: RealValued + Send + Sync,
+        P: Problem<T>
+{
+    dimension: usize,
+    size: usize,
+    weight: f32,
+    crossover_rate: f32,
+    bounds: (f32, f32),
+    debug_progress: bool,
+    solve: Arc<RwLock<P>>,
+    phantom: PhantomData<T>
+}
+
+
+
+/// implement differential evolution
+impl<T, P> DifferentialEvolution<T, P>
+    where
+        T: RealValued + Clone + Send + Sync,
+        P: Problem<T> + Send + Sync
+{
+
+    /// base differential evolution settings
+    pub fn new() -> Self {
+        DifferentialEvolution {
+            // number of real values in each member, this must be set
+            dimension: 0,
+            // number of members in the population, needs to be at least four
+            size: 50,
+            // the differential weight (F) which scales the difference vector
+            weight: 0.5,
+            // the probability of taking a value from the mutant vector instead of the target (CR)
+            crossover_rate: 0.9,
+            // the range the initial population is sampled from and trials are clamped to
+            bounds: (-1.0, 1.0),
+            // print the state of the search to the screen
+            debug_progress: false,
+            // the problem to solve, shared between threads the same way the population does it
+            solve: Arc::new(RwLock::new(P::empty())),
+            phantom: PhantomData
+        }
+    }
+
+    /// Run the optimizer according to a user defined function, the inputs of which are
+    /// the top member of the current generation, the fitness of that member, and the
+    /// current number of generations. This will continue until the function returns true,
+    /// the report holds the top member re-scored on the full problem
+    pub fn run<F>(&mut self, runner: F) -> Result<RunReport<T, ()>, &'static str>
+        where F: Fn(&T, f32, i32) -> bool + Sized
+    {
+        if self.dimension == 0 {
+            return Err("Dimension not set");
+        }
+        if self.size < 4 {
+            return Err("Differential evolution needs a population of at least four");
+        }
+        let start = Instant::now();
+        let mut r = rand::thread_rng();
+        let seed = r.gen::<u64>();
+        let (lower, upper) = self.bounds;
+        // create and score a random population within the bounds, the initial population is solved
+        // as generation zero and the trials of each generation after it
+        let mut population = (0..self.size)
+            .map(|_| T::from_values((0..self.dimension).map(|_| r.gen_range(lower, upper)).collect()))
+            .collect::<Vec<_>>();
+        let mut scores = self.evaluate(&mut population, seed, 0, 0);
+        let mut history = Vec::new();
+        let mut evaluations = self.size;
+        let mut index = 0;
+        loop {
+            // build a trial for each member and replace the member if the trial is at least as good
+            let mut trials = (0..self.size)
+                .map(|i| self.trial(&mut r, &population, i))
+                .collect::<Vec<_>>();
+            let trial_scores = self.evaluate(&mut trials, seed, index as usize + 1, evaluations);
+            evaluations += self.size;
+            for (i, (trial, score)) in trials.into_iter().zip(trial_scores).enumerate() {
+                if score >= scores[i] {
+                    population[i] = trial;
+                    scores[i] = score;
+                }
+            }
+            history.push(GenerationStats::from_scores(index as usize, 1, &scores));
+            // report the best member of the generation to the runner
+            let top = (0..self.size)
+                .fold(0, |top, i| if scores[i] > scores[top] { i } else { top });
+            if self.debug_progress {
+                println!("DE: generation( {} ) score( {:.5} )", index, scores[top]);
+            }
+            if runner(&population[top], scores[top], index) {
+                let mut champion = population[top].clone();
+                let fitness = self.solve.read().unwrap().solve_full(&mut champion);
+                return Ok(RunReport {
+                    champion,
+                    fitness,
+                    environment: (),
+                    history,
+                    evaluations,
+                    elapsed: start.elapsed(),
+                    stop: StopReason::Satisfied,
+                    species: Vec::new()
+                });
+            }
+            index += 1;
+        }
+    }
+
+    /// DE/rand/1/bin - pick three distinct members which aren't the target, build the mutant
+    /// r1 + F * (r2 - r3), then take each value from the mutant with probability CR. One random
+    /// value is always taken from the mutant so the trial is never a copy of the target
+    fn trial(&self, r: &mut ThreadRng, population: &[T], target: usize) -> T {
+        let mut picks = Vec::with_capacity(3);
+        while picks.len() < 3 {
+            let pick = r.gen_range(0, population.len());
+            if pick != target && !picks.contains(&pick) {
+                picks.push(pick);
+            }
+        }
+        let (one, two, three) = (population[picks[0]].values(), population[picks[1]].values(), population[picks[2]].values());
+        let current = population[target].values();
+        let forced = r.gen_range(0, self.dimension);
+        let (lower, upper) = self.bounds;
+        T::from_values((0..self.dimension)
+            .map(|j| {
+                if j == forced || r.gen::<f32>() < self.crossover_rate {
+                    (one[j] + self.weight * (two[j] - three[j])).max(lower).min(upper)
+                } else {
+                    current[j]
+                }
+            })
+            .collect())
+    }
+
+    /// score the members against the problem in parallel, each one is solved with a context seeded
+    /// from the run's seed, the generation, and its index. Its id is the number of members solved before it
+    fn evaluate(&self, members: &mut [T], seed: u64, generation: usize, taken: usize) -> Vec<f32> {
+        let cancel = Arc::new(AtomicBool::new(false));
+        members.par_iter_mut()
+            .enumerate()
+            .map_with(Arc::clone(&self.solve), |problem, (index, member)| {
+                let mut context = EvalContext {
+                    generation,
+                    index,
+                    id: (taken + index) as u64,
+                    species: None,
+                    rng: StdRng::seed_from_u64(random::mix(seed, generation as u64, index as u64)),
+                    cancel: Arc::clone(&cancel)
+                };
+                problem.read().unwrap().solve_with_context(member, &mut context)
+            })
+            .collect()
+    }
+
+    ///////////////////////////////////////////////////////////////////////////////////////////
+    //  configure the settings for the optimizer, all have defaults except for the dimension  //
+    ///////////////////////////////////////////////////////////////////////////////////////////
+
+    /// set the number of real values in each member
+    pub fn dimension(mut self, dimension: usize) -> Self {
+        self.dimension = dimension;
+        self
+    }
+
+    /// set the number of members in the population
+    pub fn size(mut self, size: usize) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// set the differential weight (F), normally somewhere in (0, 2]
+    pub fn weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// set the binomial crossover rate (CR)
+    pub fn crossover_rate(mut self, rate: f32) -> Self {
+        self.crossover_rate = rate;
+        self
+    }
+
+    /// set the range the population is created in and trials are clamped to
+    pub fn bounds(mut self, lower: f32, upper: f32) -> Self {
+        self.bounds = (lower, upper);
+        self
+    }
+
+    /// debug determines what to display to the screen during the search
+    pub fn debug(mut self, d: bool) -> Self {
+        self.debug_progress = d;
+        self
+    }
+
+    /// Impose a problem on the optimizer, this is the same problem
+    /// that would be given to a population
+    pub fn impose(mut self, prob: P) -> Self {
+        self.solve = Arc::new(RwLock::new(prob));
+        self
+    }
+}
+
+
+
+impl<T, P> Default for DifferentialEvolution<T, P>
+    where
+        T: RealValued + Clone + Send + Sync,
+        P: Problem<T> + Send + Sync
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,154 @@
+//! A per-generation cache of the distances between members. Genome::distance can be expensive
+//! (Neat walks its edges for every call), so a distance a generation needs is only computed once
+//! and then shared by speciation, diversity statistics and distance-aware selection. Computing every
+//! pair up front costs more than speciation itself, so exact caching fills the cache as distances are
+//! asked for. Sampled caching compares each member to a random sample of the others up front in
+//! parallel, which is enough to estimate the diversity of the population.
+
+extern crate rand;
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+use rand::seq::index;
+use rayon::prelude::*;
+
+
+
+/// Which pairs of members get their distance cached up front
+///
+/// Exact - every pair of members, each distance is cached the first time it is asked for instead of up front
+/// Sampled - each member against the given number of random other members, computed up front
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Sampling {
+    Exact,
+    Sampled(usize)
+}
+
+
+
+/// The distances between members of a generation, looked up by the members' index in the generation.
+/// Distances are symmetric so a pair is only stored once. If the cache is filled on demand the
+/// pairs are behind a lock so distances found while the generation is shared between threads are kept
+#[derive(Debug, Default)]
+pub struct DistanceCache {
+    pairs: RwLock<HashMap<(usize, usize), f32>>,
+    on_demand: bool
+}
+
+
+
+impl DistanceCache {
+
+    pub fn new() -> Self {
+        DistanceCache {
+            pairs: RwLock::new(HashMap::new()),
+            on_demand: false
+        }
+    }
+
+    /// Fill the cache with the distances between the pairs of members picked by the sampling.
+    /// The distance function is given the indexes of two members and is run in parallel.
+    /// Sampling::Exact computes nothing here, it turns on caching every distance asked for through get_or_compute
+    pub fn compute<F>(&mut self, size: usize, sampling: &Sampling, distance: F)
+        where F: Fn(usize, usize) -> f32 + Send + Sync
+    {
+        let mut pairs = match sampling {
+            Sampling::Exact => {
+                self.on_demand = true;
+                return;
+            },
+            Sampling::Sampled(num) => {
+                let mut r = rand::thread_rng();
+                let num = (*num).min(size.saturating_sub(1));
+                (0..size)
+                    .flat_map(|i| {
+                        // sample from everyone but i by shifting the indexes at or past it up by one
+                        index::sample(&mut r, size - 1, num)
+                            .into_iter()
+                            .map(|j| DistanceCache::key(i, if j >= i { j + 1 } else { j }))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>()
+            }
+        };
+        pairs.sort_unstable();
+        pairs.dedup();
+        let cached = self.pairs.get_mut().unwrap();
+        let computed = pairs
+            .into_par_iter()
+            .filter(|key| !cached.contains_key(key))
+            .map(|(i, j)| ((i, j), distance(i, j)))
+            .collect::<Vec<_>>();
+        cached.extend(computed);
+    }
+
+    /// the cached distance between two members if there is one
+    pub fn get(&self, one: usize, two: usize) -> Option<f32> {
+        if one == two {
+            return Some(0.0);
+        }
+        self.pairs.read().unwrap().get(&DistanceCache::key(one, two)).copied()
+    }
+
+    /// The cached distance between two members, otherwise compute it. The computed
+    /// distance is only kept if the cache is filled on demand (Sampling::Exact)
+    pub fn get_or_compute<F>(&self, one: usize, two: usize, distance: F) -> f32
+        where F: FnOnce() -> f32
+    {
+        if let Some(cached) = self.get(one, two) {
+            return cached;
+        }
+        let computed = distance();
+        if self.on_demand {
+            self.pairs.write().unwrap().insert(DistanceCache::key(one, two), computed);
+        }
+        computed
+    }
+
+    /// cache the distance between two members
+    pub fn insert(&mut self, one: usize, two: usize, distance: f32) {
+        self.pairs.get_mut().unwrap().insert(DistanceCache::key(one, two), distance);
+    }
+
+    /// the number of distances cached
+    pub fn len(&self) -> usize {
+        self.pairs.read().unwrap().len()
+    }
+
+    /// true if no distances are cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// forget every distance, the indexes are only good for the generation they were computed for
+    pub fn clear(&mut self) {
+        self.pairs.get_mut().unwrap().clear();
+    }
+
+    /// The mean distance over the cached pairs, a measure of how diverse the generation is.
+    /// With sampled distances this is an estimate of the mean over every pair, filled on demand
+    /// it is the mean over the distances speciation and selection asked for
+    pub fn mean(&self) -> f32 {
+        let pairs = self.pairs.read().unwrap();
+        if pairs.is_empty() {
+            return 0.0;
+        }
+        pairs.values().sum::<f32>() / pairs.len() as f32
+    }
+
+    /// pairs are stored smallest index first
+    fn key(one: usize, two: usize) -> (usize, usize) {
+        if one < two { (one, two) } else { (two, one) }
+    }
+}
+
+
+
+impl Clone for DistanceCache {
+    fn clone(&self) -> Self {
+        DistanceCache {
+            pairs: RwLock::new(self.pairs.read().unwrap().clone()),
+            on_demand: self.on_demand
+        }
+    }
+}
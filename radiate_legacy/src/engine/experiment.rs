@@ -0,0 +1,269 @@
+//! Independent repeated runs of the same population setup. Evolution is noisy so comparing two
+//! setups on a single run each says very little - an experiment runs a population factory a number
+//! of times (in parallel if wanted), keeps the best fitness curve of every run, and aggregates the
+//! runs into robust statistics: the median and interquartile range of the final best fitness, the
+//! success rate against a target, and a rank-sum test to tell if two setups really differ.
+
+extern crate rayon;
+extern crate serde_json;
+
+use std::fmt;
+use std::fmt::Debug;
+use std::fs::File;
+use std::error::Error;
+use std::time::Duration;
+use rayon::prelude::*;
+use super::{
+    genome::Genome,
+    problem::Problem,
+    environment::Envionment,
+    population::Population,
+    stats,
+    random
+};
+
+
+
+/// The experiment setup, how many runs, what seed the first run gets, and what counts as a success
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Experiment {
+    pub runs: usize,
+    pub seed: u64,
+    pub target: Option<f32>,
+    pub parallel: bool
+}
+
+
+
+/// A single run of an experiment
+///
+/// seed: the seed the population factory was given
+/// curve: the best fitness of every generation, empty if the run failed
+/// best: the best fitness of the run, f32::MIN if the run failed
+/// evaluations: the number of members the problem solved
+/// elapsed: the wall clock time of the run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExperimentRun {
+    pub seed: u64,
+    pub curve: Vec<f32>,
+    pub best: f32,
+    pub evaluations: usize,
+    pub elapsed: Duration
+}
+
+
+
+/// Every run of an experiment and the target they were held to
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExperimentReport {
+    pub target: Option<f32>,
+    pub runs: Vec<ExperimentRun>
+}
+
+
+
+/// The result of a Mann-Whitney rank-sum test between two experiments
+///
+/// u: the U statistic of the first experiment, the number of pairs of runs where it won (ties count half)
+/// z: the normal approximation of U, positive if the first experiment tends to have the higher best fitness
+/// p_value: the two sided p value of z, the chance of a difference at least this big if the two setups are the same
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankSum {
+    pub u: f32,
+    pub z: f32,
+    pub p_value: f32
+}
+
+
+
+impl Experiment {
+
+    /// an experiment of the given number of runs, run in parallel with seeds starting at 0 and no target
+    pub fn new(runs: usize) -> Self {
+        Experiment {
+            runs: runs.max(1),
+            seed: 0,
+            target: None,
+            parallel: true
+        }
+    }
+
+    /// the seed given to the first run, each run after gets the next seed
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// a run is a success if its best fitness reaches the target
+    pub fn target(mut self, target: f32) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    /// run the runs in parallel or one after the other, populations already train their members
+    /// in parallel so running them one at a time can be better for big populations
+    pub fn parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Run the experiment. The factory builds the population of a run given the run's seed (it is a good
+    /// idea to hand the seed to the population) and every population is run with the same run function,
+    /// the same as the one given to Population::run. A run which errors is kept as a failed run
+    pub fn run<T, E, P, F, R>(&self, factory: F, runner: R) -> ExperimentReport
+        where
+            T: Genome<T, E> + Clone + Send + Sync + Debug + PartialEq,
+            E: Envionment + Clone + Sized + Send + Sync + Default,
+            P: Problem<T> + Send + Sync,
+            F: Fn(u64) -> Population<T, E, P> + Send + Sync,
+            R: Fn(&T, f32, i32) -> bool + Send + Sync
+    {
+        let single = |seed: u64| {
+            let mut population = factory(seed);
+            match population.run(&runner) {
+                Ok(report) => ExperimentRun {
+                    seed,
+                    curve: report.fitness_curve(),
+                    best: report.fitness_curve().into_iter().fold(report.fitness, f32::max),
+                    evaluations: report.evaluations,
+                    elapsed: report.elapsed
+                },
+                Err(_) => ExperimentRun {
+                    seed,
+                    curve: Vec::new(),
+                    best: f32::MIN,
+                    evaluations: population.evaluations(),
+                    elapsed: Duration::default()
+                }
+            }
+        };
+        let seeds = (0..self.runs as u64).map(|i| self.seed + i).collect::<Vec<_>>();
+        let runs = if self.parallel {
+            seeds.into_par_iter().map(single).collect()
+        } else {
+            seeds.into_iter().map(single).collect()
+        };
+        ExperimentReport { target: self.target, runs }
+    }
+}
+
+
+
+impl ExperimentReport {
+
+    /// the best fitness of every run
+    pub fn bests(&self) -> Vec<f32> {
+        self.runs.iter().map(|x| x.best).collect()
+    }
+
+    /// the median best fitness over the runs
+    pub fn median(&self) -> f32 {
+        stats::median(&self.bests())
+    }
+
+    /// the first and third quartile of the best fitness over the runs
+    pub fn quartiles(&self) -> (f32, f32) {
+        let bests = self.bests();
+        (stats::quantile(&bests, 0.25), stats::quantile(&bests, 0.75))
+    }
+
+    /// the interquartile range of the best fitness over the runs, the spread of the middle half of the runs
+    pub fn iqr(&self) -> f32 {
+        let (lower, upper) = self.quartiles();
+        upper - lower
+    }
+
+    /// the fraction of runs which reached the target, 0 if there is no target
+    pub fn success_rate(&self) -> f32 {
+        match self.target {
+            Some(target) => stats::success_rate(self.runs.iter().map(|x| x.best >= target)),
+            None => 0.0
+        }
+    }
+
+    /// the first generation each successful run reached the target
+    pub fn generations_to_target(&self) -> Vec<usize> {
+        match self.target {
+            Some(target) => self.runs
+                .iter()
+                .filter_map(|run| run.curve.iter().position(|x| *x >= target))
+                .collect(),
+            None => Vec::new()
+        }
+    }
+
+    /// The median of the best fitness so far at every generation over the runs. Runs which
+    /// finished early hold their best fitness for the generations after they stopped
+    pub fn median_curve(&self) -> Vec<f32> {
+        let length = self.runs.iter().map(|x| x.curve.len()).max().unwrap_or(0);
+        let running = self.runs
+            .iter()
+            .filter(|x| !x.curve.is_empty())
+            .map(|run| {
+                let mut best = f32::MIN;
+                (0..length)
+                    .map(|i| {
+                        best = best.max(*run.curve.get(i).unwrap_or(&best));
+                        best
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        (0..length)
+            .map(|i| stats::median(&running.iter().map(|x| x[i]).collect::<Vec<_>>()))
+            .collect()
+    }
+
+    /// The Mann-Whitney rank-sum test of the best fitness of this experiment's runs against another's.
+    /// This only looks at the order of the results so one lucky run can't swing it like it can a mean
+    pub fn rank_sum(&self, other: &ExperimentReport) -> RankSum {
+        let (one, two) = (self.bests(), other.bests());
+        let (n1, n2) = (one.len() as f32, two.len() as f32);
+        let mut all = one.iter().map(|x| (*x, true))
+            .chain(two.iter().map(|x| (*x, false)))
+            .collect::<Vec<_>>();
+        all.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        // tied values share the mean of the ranks they span
+        let mut rank_one = 0.0;
+        let mut ties = 0.0;
+        let mut i = 0;
+        while i < all.len() {
+            let mut j = i;
+            while j + 1 < all.len() && all[j + 1].0 == all[i].0 {
+                j += 1;
+            }
+            let rank = (i + j) as f32 / 2.0 + 1.0;
+            rank_one += all[i..=j].iter().filter(|x| x.1).count() as f32 * rank;
+            let span = (j - i + 1) as f32;
+            ties += span.powi(3) - span;
+            i = j + 1;
+        }
+        let u = rank_one - n1 * (n1 + 1.0) / 2.0;
+        let n = n1 + n2;
+        let variance = if n > 1.0 { n1 * n2 / 12.0 * ((n + 1.0) - ties / (n * (n - 1.0))) } else { 0.0 };
+        if variance <= 0.0 {
+            return RankSum { u, z: 0.0, p_value: 1.0 };
+        }
+        let z = (u - n1 * n2 / 2.0) / variance.sqrt();
+        RankSum { u, z, p_value: (2.0 * (1.0 - random::normal_cdf(z.abs()))).min(1.0) }
+    }
+
+    /// save the report as json to a file path
+    pub fn save_json(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
+        serde_json::to_writer(&File::create(file_path)?, &self)?;
+        Ok(())
+    }
+}
+
+
+
+impl fmt::Display for ExperimentReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (lower, upper) = self.quartiles();
+        write!(f, "runs( {} ) median( {:.4} ) iqr( {:.4} - {:.4} )", self.runs.len(), self.median(), lower, upper)?;
+        if self.target.is_some() {
+            write!(f, " success( {:.2} )", self.success_rate())?;
+        }
+        Ok(())
+    }
+}
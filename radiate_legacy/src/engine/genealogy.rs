@@ -0,0 +1,217 @@
+//! Keep track of where every member of the population came from. Each member carries
+//! its own lineage (id, parents, birth generation, species, and the operators which created it),
+//! and the population can optionally record every lineage it sees into a genealogy which is
+//! the full ancestry graph of the run, exportable as JSON or GraphML.
+
+extern crate serde_json;
+
+use std::fs::File;
+use std::io::Write;
+use std::error::Error;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use uuid::Uuid;
+
+
+
+/// Member ids are unique across every population in the process
+pub type MemberId = u64;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+
+
+/// The lineage of a single member, this is held in the member's container
+/// and follows the member through every generation it survives
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lineage {
+    pub id: MemberId,
+    pub parents: Vec<MemberId>,
+    pub birth: usize,
+    pub species: Option<Uuid>,
+    pub operators: Vec<String>
+}
+
+
+
+/// A member as recorded in the genealogy, its lineage along with the
+/// fitness it had and the last generation it was seen alive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ancestor {
+    pub lineage: Lineage,
+    pub fitness: f32,
+    pub last_seen: usize
+}
+
+
+
+/// How much better (or worse) a child was than its best parent and the
+/// operators which produced it - used to see which operators actually help
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Improvement {
+    pub id: MemberId,
+    pub operators: Vec<String>,
+    pub delta: f32
+}
+
+
+
+/// The full ancestry graph of a run, every member ever recorded keyed by its id
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Genealogy {
+    pub members: BTreeMap<MemberId, Ancestor>
+}
+
+
+
+impl Lineage {
+
+    /// create the lineage for a brand new member with a fresh id
+    pub fn new(birth: usize, parents: Vec<MemberId>, operators: Vec<String>) -> Self {
+        Lineage {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            parents,
+            birth,
+            species: None,
+            operators
+        }
+    }
+}
+
+
+
+impl Genealogy {
+
+    pub fn new() -> Self {
+        Genealogy { members: BTreeMap::new() }
+    }
+
+    /// Record a member's lineage and fitness for a generation. If the member is already
+    /// in the genealogy (it survived), its species, fitness, and last seen generation are updated
+    pub fn record(&mut self, lineage: &Lineage, fitness: f32, generation: usize) {
+        let ancestor = self.members
+            .entry(lineage.id)
+            .or_insert_with(|| Ancestor {
+                lineage: lineage.clone(),
+                fitness,
+                last_seen: generation
+            });
+        ancestor.lineage.species = lineage.species;
+        ancestor.fitness = fitness;
+        ancestor.last_seen = generation;
+    }
+
+    /// get a recorded member by its id
+    pub fn get(&self, id: MemberId) -> Option<&Ancestor> {
+        self.members.get(&id)
+    }
+
+    /// the number of members recorded
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// true if nothing has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Get the sub graph of every recorded ancestor of a member, including the member itself.
+    /// This is what should be looked at when a champion shows up
+    pub fn ancestry(&self, id: MemberId) -> Genealogy {
+        let mut seen = BTreeSet::new();
+        let mut stack = vec![id];
+        while let Some(curr) = stack.pop() {
+            if let Some(ancestor) = self.members.get(&curr) {
+                if seen.insert(curr) {
+                    stack.extend(ancestor.lineage.parents.iter().cloned());
+                }
+            }
+        }
+        Genealogy {
+            members: seen.into_iter()
+                .map(|x| (x, self.members[&x].clone()))
+                .collect()
+        }
+    }
+
+    /// For every recorded child whose parents were also recorded, compute how much
+    /// better it was than its best parent along with the operators which created it
+    pub fn improvements(&self) -> Vec<Improvement> {
+        self.members
+            .values()
+            .filter_map(|child| {
+                let best_parent = child.lineage.parents
+                    .iter()
+                    .filter_map(|x| self.members.get(x))
+                    .map(|x| x.fitness)
+                    .fold(None, |best: Option<f32>, fit| Some(best.map_or(fit, |b| b.max(fit))))?;
+                Some(Improvement {
+                    id: child.lineage.id,
+                    operators: child.lineage.operators.clone(),
+                    delta: child.fitness - best_parent
+                })
+            })
+            .collect()
+    }
+
+    /// export the genealogy to a json string
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&self)
+    }
+
+    /// Export the genealogy as a GraphML document. Every member is a node holding its fitness,
+    /// birth generation, last seen generation, species, and operators, and every parent has an edge to its child
+    pub fn to_graphml(&self) -> String {
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        out.push_str("  <key id=\"fitness\" for=\"node\" attr.name=\"fitness\" attr.type=\"float\"/>\n");
+        out.push_str("  <key id=\"birth\" for=\"node\" attr.name=\"birth\" attr.type=\"int\"/>\n");
+        out.push_str("  <key id=\"last_seen\" for=\"node\" attr.name=\"last_seen\" attr.type=\"int\"/>\n");
+        out.push_str("  <key id=\"species\" for=\"node\" attr.name=\"species\" attr.type=\"string\"/>\n");
+        out.push_str("  <key id=\"operators\" for=\"node\" attr.name=\"operators\" attr.type=\"string\"/>\n");
+        out.push_str("  <graph id=\"genealogy\" edgedefault=\"directed\">\n");
+        for (id, ancestor) in self.members.iter() {
+            out.push_str(&format!("    <node id=\"n{}\">\n", id));
+            out.push_str(&format!("      <data key=\"fitness\">{}</data>\n", ancestor.fitness));
+            out.push_str(&format!("      <data key=\"birth\">{}</data>\n", ancestor.lineage.birth));
+            out.push_str(&format!("      <data key=\"last_seen\">{}</data>\n", ancestor.last_seen));
+            if let Some(species) = ancestor.lineage.species {
+                out.push_str(&format!("      <data key=\"species\">{}</data>\n", species));
+            }
+            out.push_str(&format!("      <data key=\"operators\">{}</data>\n", escape(&ancestor.lineage.operators.join(","))));
+            out.push_str("    </node>\n");
+        }
+        for (id, ancestor) in self.members.iter() {
+            for parent in ancestor.lineage.parents.iter().filter(|x| self.members.contains_key(x)) {
+                out.push_str(&format!("    <edge source=\"n{}\" target=\"n{}\"/>\n", parent, id));
+            }
+        }
+        out.push_str("  </graph>\n");
+        out.push_str("</graphml>\n");
+        out
+    }
+
+    /// save the genealogy as json to a file path
+    pub fn save_json(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
+        serde_json::to_writer(&File::create(file_path)?, &self)?;
+        Ok(())
+    }
+
+    /// save the genealogy as GraphML to a file path
+    pub fn save_graphml(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
+        File::create(file_path)?.write_all(self.to_graphml().as_bytes())?;
+        Ok(())
+    }
+}
+
+
+
+/// escape the characters xml cares about so user defined operator names can't break the document
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
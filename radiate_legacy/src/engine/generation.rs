@@ -1,51 +1,81 @@
 extern crate rand;
 
+use std::mem;
 use std::sync::{Arc, Weak, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::marker::PhantomData;
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 use rayon::prelude::*;
+use uuid::Uuid;
 use super::niche::{Niche, NicheMember};
 use super::{
     genome::Genome,
-    problem::Problem,
+    problem::{Problem, EvalContext},
+    random,
     environment::Envionment,
     population::Config,
-    survival::{SurvivalCriteria, ParentalCriteria}
+    operator::Pipeline,
+    genealogy::{Lineage, Genealogy},
+    distance::{DistanceCache, Sampling},
+    probes::Probes,
+    survival::{SurvivalCriteria, ParentalCriteria, Parent}
 };
 
 
 
 
-/// The member type is meant to represent a holder for 
-/// just the type T, where it has an owning reference counter
-/// then wrapped in a ref cell to allow for mutable borrowing of the Rc
+/// Members and species used to be shared behind locks, now a generation owns its members and
+/// species directly and species refer to members by index. These are kept so code written
+/// against the old types still compiles while it moves over
+#[deprecated = "containers own their member, use T"]
 pub type Member<T> = Arc<RwLock<T>>;
-/// the MemberWeak is meant to be a non-owning member type pointing to the
-/// same memory space but not having the same owning ability of the data
+#[deprecated = "containers own their member, use T"]
 pub type MemberWeak<T> = Weak<RwLock<T>>;
-
-/// A family is a wrapper for a species type which owns the data it
-/// holds. This is needed as there are many references to a species 
-/// throughout the program
+#[deprecated = "generations own their species, use Niche"]
 pub type Family<T, E> = Arc<RwLock<Niche<T, E>>>;
-/// the FamilyWeak is meant to mimic the MemberWeak as it is a non-owning family
-/// type which allows for multiple bi-directional pointers to the same Niche 
-/// type in the same memory location
+#[deprecated = "generations own their species, use Niche"]
 pub type FamilyWeak<T, E> = Weak<RwLock<Niche<T, E>>>;
 
 
 
+/// the number of times a child is bred before giving up on making a valid one, or
+/// any child at all if the crossover keeps failing. After that the child is a copy of its fitter parent
+pub(crate) const BREED_ATTEMPTS: usize = 10;
+
+
 
 /// A container is a simple container to encapsulate a member (Type T)
-/// its fitness score for the current generation, and a weak reference 
-/// counting cell to the species it belongs to
-#[derive(Debug)]
+/// its fitness score for the current generation, the id of the species it
+/// belongs to, the lineage of the member, its age - the number of generations
+/// its genetic material has been evolving, and how much it violates the problem's
+/// constraints. Members are owned by the generation's vec of containers and
+/// species refer to them by their index, so nothing has to be locked to read them.
+/// If the population handles constraints the fitness score is the score used for
+/// selection and the objective is the problem's fitness. A member is predicted if a
+/// surrogate model gave it its fitness instead of the problem. With adaptive species
+/// a child also remembers the species whose environment it was bred with and the
+/// objective of its fittest parent, so the species can tell how well its environment is working.
+/// With behavioural speciation the member's signature is its outputs on the probes
+#[derive(Debug, Clone)]
 pub struct Container<T, E>
     where 
         T: Genome<T, E> + Send + Sync,
         E: Send + Sync
 {
-    pub member: Member<T>,
+    pub member: T,
     pub fitness_score: f32,
-    pub species: Option<FamilyWeak<T, E>>
+    pub species: Option<Uuid>,
+    pub lineage: Lineage,
+    pub age: usize,
+    pub objective: f32,
+    pub violation: f32,
+    pub predicted: bool,
+    pub bred_by: Option<Uuid>,
+    pub parent_objective: f32,
+    pub signature: Option<Vec<f32>>,
+    phantom: PhantomData<E>
 }
 
 
@@ -54,12 +84,35 @@ impl<T, E> Container<T, E>
         T: Genome<T, E> + Send + Sync,
         E: Send + Sync
 {
-    pub fn get_member(&mut self) -> &mut Member<T> {
+    /// wrap a member with its lineage, it has no fitness or species until it is optimized and speciated
+    pub fn new(member: T, lineage: Lineage) -> Self {
+        Container {
+            member,
+            fitness_score: 0.0,
+            species: None,
+            lineage,
+            age: 0,
+            objective: 0.0,
+            violation: 0.0,
+            predicted: false,
+            bred_by: None,
+            parent_objective: 0.0,
+            signature: None,
+            phantom: PhantomData
+        }
+    }
+
+    /// true if the member doesn't violate any constraints
+    pub fn is_feasible(&self) -> bool {
+        self.violation <= 0.0
+    }
+
+    pub fn get_member(&mut self) -> &mut T {
         &mut self.member
     }
 
     pub fn update_member(&mut self, new_member: T) {
-        *self.member.write().unwrap() = new_member;
+        self.member = new_member;
     }
 
     pub fn set_fitness(&mut self, fitness: f32) {
@@ -71,7 +124,10 @@ impl<T, E> Container<T, E>
 
 /// A generation is meant to facilitate the speciation, crossover, and 
 /// reproduction of species and their types over the course of a single
-/// generation
+/// generation. It also caches the distances between its members which
+/// are computed during the generation, and holds the probes members are
+/// run on if the generation is speciated by behaviour. It also counts how many of
+/// the children bred into it were invalid and had to be repaired or thrown away
 #[derive(Debug)]
 pub struct Generation<T, E> 
     where
@@ -79,9 +135,15 @@ pub struct Generation<T, E>
         E: Send + Sync
 {
     pub members: Vec<Container<T, E>>,
-    pub species: Vec<Family<T, E>>,
+    pub species: Vec<Niche<T, E>>,
     pub survival_criteria: SurvivalCriteria,
-    pub parental_criteria: ParentalCriteria
+    pub parental_criteria: ParentalCriteria,
+    pub operators: Option<Arc<Pipeline<T, E>>>,
+    pub distances: DistanceCache,
+    pub probes: Option<Arc<Probes<T>>>,
+    pub index: usize,
+    pub repairs: usize,
+    pub discards: usize
 }
 
 
@@ -101,33 +163,45 @@ impl<T, E> Generation<T, E>
             members: Vec::new(),
             species: Vec::new(),
             survival_criteria: SurvivalCriteria::Fittest,
-            parental_criteria: ParentalCriteria::BiasedRandom
+            parental_criteria: ParentalCriteria::BiasedRandom,
+            operators: None,
+            distances: DistanceCache::new(),
+            probes: None,
+            index: 0,
+            repairs: 0,
+            discards: 0
         }
     }
 
-    /// pass down the previous generation's members and species to a new generation
+    /// pass down the previous generation's members and species to a new generation,
+    /// the members are treated as brand new and are given a fresh lineage
+    #[inline]
+    pub fn pass_down(&mut self, new_members: Vec<T>) -> Option<Self> {
+        let birth = self.index + 1;
+        self.descend(new_members
+            .into_par_iter()
+            .map(|x| Container::new(x, Lineage::new(birth, Vec::new(), Vec::new())))
+            .collect())
+    }
+
+    /// pass down already created containers and the previous generation's species to a new generation,
+    /// the species are reset with a new mascot from this generation's members as they go
     #[inline]
-    pub fn pass_down(&self, new_members: Vec<Member<T>>) -> Option<Self> {
+    fn descend(&mut self, members: Vec<Container<T, E>>) -> Option<Self> {
+        let old = &self.members;
+        let mut species = mem::take(&mut self.species);
+        species.par_iter_mut().for_each(|spec| spec.reset(old));
         Some(Generation {
-            members: new_members
-                .into_par_iter()
-                .map(|x| {
-                    Container {
-                        member: Arc::clone(&x),
-                        fitness_score: 0.0,
-                        species: None
-                    }
-                })
-                .collect(),
-            species: self.species
-                .par_iter()
-                .map(|spec| {
-                    spec.write().unwrap().reset();
-                    Arc::clone(spec)
-                })
-                .collect(),
+            members,
+            species,
             survival_criteria: self.survival_criteria.clone(),
-            parental_criteria: self.parental_criteria.clone()
+            parental_criteria: self.parental_criteria.clone(),
+            operators: self.operators.clone(),
+            distances: DistanceCache::new(),
+            probes: self.probes.clone(),
+            index: self.index + 1,
+            repairs: 0,
+            discards: 0
         })
     }
 
@@ -146,55 +220,136 @@ impl<T, E> Generation<T, E>
         self.members.get(idx)
     }
 
-    /// The optimization function
+    /// The optimization function, members are solved with a context seeded from a random seed
     #[inline]
     pub fn optimize<P>(&mut self, prob: Arc<RwLock<P>>)
         where P: Problem<T> + Send + Sync
     {
+        self.optimize_seeded(prob, rand::thread_rng().gen(), &Arc::new(AtomicBool::new(false)));
+    }
+
+    /// Optimize the members, giving each one a context with a random number generator
+    /// seeded from the seed, the generation index, and the member's index in the generation.
+    /// Members already given a fitness by a surrogate model are not solved again. If the generation
+    /// has probes every member is run on them for its signature as well
+    #[inline]
+    pub fn optimize_seeded<P>(&mut self, prob: Arc<RwLock<P>>, seed: u64, cancel: &Arc<AtomicBool>)
+        where P: Problem<T> + Send + Sync
+    {
+        let generation = self.index;
+        let probes = self.probes.clone();
         // concurrently iterate the members and optimize them
         self.members
             .par_iter_mut()
-            .for_each_with(prob, |problem, cont| {
-                (*cont).fitness_score = problem.read().unwrap().solve(&mut *cont.member.write().unwrap());
+            .enumerate()
+            .for_each_with(prob, |problem, (index, cont)| {
+                let mut context = EvalContext {
+                    generation,
+                    index,
+                    id: cont.lineage.id,
+                    species: cont.lineage.species,
+                    rng: StdRng::seed_from_u64(random::mix(seed, generation as u64, index as u64)),
+                    cancel: Arc::clone(cancel)
+                };
+                let problem = problem.read().unwrap();
+                if !cont.predicted {
+                    cont.fitness_score = problem.solve_with_context(&mut cont.member, &mut context);
+                }
+                cont.objective = cont.fitness_score;
+                cont.violation = problem.constraint_violation(&cont.member);
+                if let Some(probes) = probes.as_ref() {
+                    cont.signature = Some(probes.signature(&mut cont.member));
+                }
             });
     }
 
+    /// Cache the distances between the pairs of members picked by the sampling. Sampled pairs are computed
+    /// up front in parallel, Sampling::Exact caches every distance between members as it is asked for
+    pub fn cache_distances(&mut self, sampling: &Sampling, settings: Arc<RwLock<E>>) {
+        let members = &self.members;
+        let probes = self.probes.as_deref();
+        self.distances.compute(members.len(), sampling, |one, two| {
+            Generation::between(probes, &members[one].member, members[one].signature.as_deref(), &members[two].member, members[two].signature.as_deref(), Arc::clone(&settings))
+        });
+    }
+
+    /// The distance between two members of the generation, from the cache if it is there
+    pub fn distance(&self, one: usize, two: usize, settings: Arc<RwLock<E>>) -> f32 {
+        self.distances.get_or_compute(one, two, || {
+            let (one, two) = (&self.members[one], &self.members[two]);
+            Generation::between(self.probes.as_deref(), &one.member, one.signature.as_deref(), &two.member, two.signature.as_deref(), settings)
+        })
+    }
+
+    /// The distance between two members given their signatures, by behaviour if there
+    /// are probes and both have a signature, otherwise the genome's distance
+    fn between(probes: Option<&Probes<T>>, one: &T, one_signature: Option<&[f32]>, two: &T, two_signature: Option<&[f32]>, settings: Arc<RwLock<E>>) -> f32 {
+        let genome = || <T as Genome<T, E>>::distance(one, two, settings);
+        match probes {
+            Some(probes) => probes.distance(one_signature, two_signature, genome),
+            None => genome()
+        }
+    }
+
     /// Speciation is the process of going through the members in the generation
     /// and assigning them species in which they belong to determined by a specific 
-    /// distance between the member and the species mascot.
+    /// distance between the member and the species mascot. The distances to the
+    /// species carried over from the last generation are computed up front in parallel,
+    /// species founded during speciation have a member as their mascot so the distances
+    /// between members are used for them, through the cache if there is one
     #[inline]
     pub fn speciate(&mut self, distance: f32, settings: Arc<RwLock<E>>) {
-        // Loop over the members mutably to find a species which this member belongs to
-        for cont in self.members.iter_mut() {
-            // see if this member belongs to a given species 
-            let mem_spec = self.species
-                .iter()
+        let to_mascots = self.members
+            .par_iter()
+            .map(|cont| {
+                self.species
+                    .iter()
+                    .map(|s| Generation::between(self.probes.as_deref(), &cont.member, cont.signature.as_deref(), &s.mascot, s.signature.as_deref(), Arc::clone(&settings)))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        // Loop over the members to find a species which this member belongs to
+        for (index, known) in to_mascots.iter().enumerate() {
+            // see if this member belongs to a given species, the founder of a new species is its first member
+            let mem_spec = (0..self.species.len())
                 .find(|s| {
-                    <T as Genome<T, E>>::distance(&*cont.member.read().unwrap(), &*s.read().unwrap().mascot.read().unwrap(), Arc::clone(&settings)) < distance
+                    let between = match known.get(*s) {
+                        Some(dist) => *dist,
+                        None => self.distance(index, self.species[*s].members[0].1, Arc::clone(&settings))
+                    };
+                    between < distance
                 });
             // if the member does belong to an existing species, add the two to each other 
             // otherwise create a new species and add that to the species and the member 
+            let cont = &mut self.members[index];
             match mem_spec {
                 Some(spec) => {
-                    let mut lock_spec = spec.write().unwrap();
-                    lock_spec.members.push(NicheMember(cont.fitness_score, Arc::downgrade(&cont.member)));
-                    cont.species = Some(Arc::downgrade(spec));
+                    let spec = &mut self.species[spec];
+                    spec.members.push(NicheMember(cont.fitness_score, index));
+                    cont.species = Some(spec.niche_id);
                 },
                 None => {
-                    let new_family = Arc::new(RwLock::new(Niche::new(&cont.member, cont.fitness_score)));
-                    cont.species = Some(Arc::downgrade(&new_family));
+                    let mut new_family = Niche::new(&cont.member, cont.fitness_score, index);
+                    new_family.signature = cont.signature.clone();
+                    cont.species = Some(new_family.niche_id);
                     self.species.push(new_family);
                 }
             }
+            cont.lineage.species = cont.species;
         }
         // first filter out all species with have died out.
         // go through and set the total adjusted fitness for each species
-        self.species.retain(|x| Arc::weak_count(&x) > 0);
-        for i in self.species.iter() {
-            i.write().unwrap().calculate_total_adjusted_fitness();
+        self.species.retain(|x| !x.members.is_empty());
+        for i in self.species.iter_mut() {
+            i.calculate_total_adjusted_fitness();
         }
     }
 
+    /// Get a species of the generation by its id
+    pub fn species_by_id(&self, id: Uuid) -> Option<&Niche<T, E>> {
+        self.species.iter().find(|x| x.niche_id == id)
+    }
+
     /// Create the next generation and return a new generation struct with 
     /// new members, and reset species. This is how the generation moves from
     /// one to the next. This function also is the one which runs the crossover
@@ -202,40 +357,258 @@ impl<T, E> Generation<T, E>
     /// this function will be.
     #[inline]
     pub fn create_next_generation(&mut self, pop_size: i32, config: Config, env: Arc<RwLock<E>>) -> Option<Self> {   
+        self.breed(None, pop_size, config, env)
+    }
+
+    /// Create the next generation the same way as create_next_generation, but parents can also
+    /// be picked from the species of another generation. This is used by age layers where members
+    /// compete with those in their own layer and the layer below. Survivors only come from this generation.
+    #[inline]
+    pub fn create_next_generation_from(&mut self, below: &Generation<T, E>, pop_size: i32, config: Config, env: Arc<RwLock<E>>) -> Option<Self> {
+        self.breed(Some(below), pop_size, config, env)
+    }
+
+    /// pick the survivors then breed children from the parents to fill the rest of the next generation
+    #[inline]
+    fn breed(&mut self, below: Option<&Generation<T, E>>, pop_size: i32, config: Config, env: Arc<RwLock<E>>) -> Option<Self> {
         // generating new members in a biased way using rayon to parallelize it
         // then crossover to fill the rest of the generation 
-        let mut new_members = self.survival_criteria.pick_survivors(&mut self.members, &self.species)?;
+        let mut survivors = self.survival_criteria.survivors(&self.members, &self.species)?;
+        survivors.sort_unstable();
+        survivors.dedup();
+        // if the population shrank below the number of survivors only the fittest of them survive
+        if survivors.len() > pop_size.max(0) as usize {
+            survivors.sort_by(|a, b| self.members[*b].fitness_score.partial_cmp(&self.members[*a].fitness_score).unwrap());
+            survivors.truncate(pop_size.max(0) as usize);
+        }
+        // parents can come from this generation's species or the ones below it, the species
+        // past the end of this generation's point to members in the generation below
+        let families = self.species
+            .iter()
+            .chain(below.iter().flat_map(|gen| gen.species.iter()))
+            .collect::<Vec<_>>();
+        let split = self.species.len();
+        let container_of = |species: usize, index: usize| match below {
+            Some(gen) if species >= split => &gen.members[index],
+            _ => &self.members[index]
+        };
+        // the distance between two parents, cached if they are both in this generation
+        let distance = |one: &Parent, two: &Parent| {
+            if one.1 < split && two.1 < split {
+                return self.distance(one.2, two.2, Arc::clone(&env));
+            }
+            let (one, two) = (container_of(one.1, one.2), container_of(two.1, two.2));
+            Generation::between(self.probes.as_deref(), &one.member, one.signature.as_deref(), &two.member, two.signature.as_deref(), Arc::clone(&env))
+        };
+        let birth = self.index + 1;
+        let (repairs, discards) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        // survivors keep their lineage and get a year older
+        let mut new_members = survivors
+            .into_iter()
+            .map(|i| {
+                let cont = &self.members[i];
+                let mut survivor = Container::new(cont.member.clone(), cont.lineage.clone());
+                survivor.age = cont.age + 1;
+                survivor
+            })
+            .collect::<Vec<_>>();
         let children = (new_members.len() as i32..pop_size)
             .into_par_iter()
-            .map(|_|{
-                // select two random species to crossover, with a chance of inbreeding then cross them over
-                let (one, two) = self.parental_criteria.pick_parents(config.inbreed_rate, &self.species).unwrap();
-                let child = if one.0 > two.0 {
-                    <T as Genome<T, E>>::crossover(&*one.1.read().unwrap(), &*two.1.read().unwrap(), Arc::clone(&env), config.crossover_rate).unwrap()
-                } else {
-                    <T as Genome<T, E>>::crossover(&*two.1.read().unwrap(), &*one.1.read().unwrap(), Arc::clone(&env), config.crossover_rate).unwrap()
-                };
-                Arc::new(RwLock::new(child))
+            .map(|_| {
+                let mut attempts = 0;
+                loop {
+                    attempts += 1;
+                    // select two random species to crossover, with a chance of inbreeding then cross them over
+                    let (one, two) = self.parental_criteria.pick_parents_with(config.inbreed_rate, &families, distance).unwrap();
+                    let (fit, weak) = if one.0 > two.0 { (one, two) } else { (two, one) };
+                    let (fit_spec, fit, weak) = (families[fit.1], container_of(fit.1, fit.2), container_of(weak.1, weak.2));
+                    // the child is bred with the environment of the fitter parent's species if it has its own
+                    let env = match &fit_spec.environment {
+                        Some(spec_env) => Arc::clone(spec_env),
+                        None => Arc::clone(&env)
+                    };
+                    // run the parents through the operator pipeline if there is one, otherwise let the genome's crossover decide
+                    let bred = match &self.operators {
+                        Some(pipeline) => pipeline.apply_traced(&fit.member, &weak.member, Arc::clone(&env)),
+                        None => <T as Genome<T, E>>::crossover(&fit.member, &weak.member, Arc::clone(&env), config.crossover_rate)
+                            .map(|child| (child, vec![String::from("crossover")]))
+                    };
+                    // if an operator or the crossover couldn't make a child, try again with new parents
+                    let (child, mut operators) = match bred {
+                        Some(bred) => bred,
+                        None if attempts < BREED_ATTEMPTS => continue,
+                        None => (fit.member.clone(), vec![String::from("clone")])
+                    };
+                    // an invalid child is repaired if it can be, otherwise it is thrown away and bred again
+                    let child = match Generation::check(child, &env) {
+                        Ok(child) => child,
+                        Err(Some(fixed)) => {
+                            repairs.fetch_add(1, Ordering::Relaxed);
+                            operators.push(String::from("repair"));
+                            fixed
+                        },
+                        Err(None) => {
+                            discards.fetch_add(1, Ordering::Relaxed);
+                            if attempts < BREED_ATTEMPTS {
+                                continue;
+                            }
+                            operators = vec![String::from("clone")];
+                            fit.member.clone()
+                        }
+                    };
+                    // record who the parents were, inbred children only have one. The child
+                    // is one generation older than the oldest genetic material it came from
+                    let mut ids = vec![fit.lineage.id, weak.lineage.id];
+                    ids.dedup();
+                    let mut child = Container::new(child, Lineage::new(birth, ids, operators));
+                    child.age = fit.age.max(weak.age) + 1;
+                    child.bred_by = fit_spec.environment.as_ref().map(|_| fit_spec.niche_id);
+                    child.parent_objective = fit.objective.max(weak.objective);
+                    break child;
+                }
             })
             .collect::<Vec<_>>();
         // reset the species and pass down the new members to a new generation
         new_members.extend(children);
-        self.pass_down(new_members)
+        let mut next = self.descend(new_members)?;
+        next.repairs = repairs.into_inner();
+        next.discards = discards.into_inner();
+        Some(next)
+    }
+
+    /// Check a child is valid. Ok if it is, otherwise Err with the repaired child
+    /// if the genome could repair it into a valid one, or None if it couldn't
+    pub fn check(child: T, env: &Arc<RwLock<E>>) -> Result<T, Option<T>> {
+        if <T as Genome<T, E>>::validate(&child, Arc::clone(env)) {
+            return Ok(child);
+        }
+        Err(<T as Genome<T, E>>::repair(&child, Arc::clone(env))
+            .filter(|fixed| <T as Genome<T, E>>::validate(fixed, Arc::clone(env))))
+    }
+
+    /// Adapt the environment of every species by how many of the children bred with it this
+    /// generation beat their fittest parent. Call this after the generation has been optimized
+    pub fn adapt_species(&self) {
+        for spec in self.species.iter() {
+            if let Some(env) = spec.environment.as_ref() {
+                let (successes, total) = self.members
+                    .iter()
+                    .filter(|x| !x.predicted && x.bred_by == Some(spec.niche_id))
+                    .fold((0, 0), |(successes, total), x| (successes + (x.objective > x.parent_objective) as usize, total + 1));
+                if total > 0 {
+                    env.write().unwrap().adapt(successes as f32 / total as f32);
+                }
+            }
+        }
+    }
+
+    /// Give every species without an environment its own. A new species copies the environment its
+    /// founding member was bred with so the settings carry down the lineage, or the global one if there wasn't one
+    pub fn spawn_environments(&mut self, global: &Arc<RwLock<E>>, spawn: fn(&E) -> E) {
+        let inherited = self.species
+            .iter()
+            .map(|spec| {
+                if spec.environment.is_some() {
+                    return None;
+                }
+                let parent = spec.members.first()
+                    .and_then(|x| self.members[x.1].bred_by)
+                    .and_then(|id| self.species_by_id(id))
+                    .and_then(|x| x.environment.as_ref());
+                Some(match parent {
+                    Some(env) => spawn(&*env.read().unwrap()),
+                    None => spawn(&*global.read().unwrap())
+                })
+            })
+            .collect::<Vec<_>>();
+        for (spec, env) in self.species.iter_mut().zip(inherited) {
+            if let Some(env) = env {
+                spec.environment = Some(Arc::new(RwLock::new(env)));
+            }
+        }
+    }
+
+    /// record every member of the generation along with the problem's fitness of them into a genealogy
+    pub fn record(&self, genealogy: &mut Genealogy) {
+        for cont in self.members.iter() {
+            genealogy.record(&cont.lineage, cont.objective, self.index);
+        }
     }
 
-    /// get the top member of the generations
+    /// get the top member of the generations, members who only have a predicted fitness don't count
     #[inline] 
     pub fn best_member(&self) -> Option<(f32, Arc<T>)> {
         let mut top: Option<&Container<T, E>> = None;
-        for i in self.members.iter() {
+        for i in self.members.iter().filter(|x| !x.predicted) {
             if top.is_none() || i.fitness_score > top?.fitness_score {
                 top = Some(i);
             }
         }
         // return the best member of the generation
         match top {
-            Some(t) => Some((t.fitness_score, Arc::new((*t.member).read().unwrap().clone()))),
+            Some(t) => Some((t.fitness_score, Arc::new(t.member.clone()))),
             None => None
         }
     }
+
+    /// Get the top member of the generation after solving the top k members again with
+    /// Problem::solve_full. This is for problems which normally only solve on part of their
+    /// data, the best member is picked by its full score and that score is returned with it
+    #[inline]
+    pub fn best_member_rechecked<P>(&self, k: usize, prob: &P) -> Option<(f32, Arc<T>)>
+        where P: Problem<T> + Send + Sync
+    {
+        let mut ranked = self.members.iter().filter(|x| !x.predicted).collect::<Vec<_>>();
+        ranked.sort_by(|a, b| b.fitness_score.partial_cmp(&a.fitness_score).unwrap());
+        Self::recheck(ranked, k, prob)
+    }
+
+    /// Get the top member of the generation by the feasibility rules after solving the top k
+    /// feasible members again with Problem::solve_full, ranked by their objective. The full score
+    /// doesn't know about the constraints so infeasible members are never rechecked, if there are
+    /// no feasible members this is the same as best_feasible
+    #[inline]
+    pub fn best_feasible_rechecked<P>(&self, k: usize, prob: &P) -> Option<(f32, Arc<T>)>
+        where P: Problem<T> + Send + Sync
+    {
+        let mut ranked = self.members.iter().filter(|x| !x.predicted && x.is_feasible()).collect::<Vec<_>>();
+        if ranked.is_empty() {
+            return self.best_feasible();
+        }
+        ranked.sort_by(|a, b| b.objective.partial_cmp(&a.objective).unwrap());
+        Self::recheck(ranked, k, prob)
+    }
+
+    /// solve the first k of the ranked members again with Problem::solve_full and keep the best
+    fn recheck<P>(mut ranked: Vec<&Container<T, E>>, k: usize, prob: &P) -> Option<(f32, Arc<T>)>
+        where P: Problem<T> + Send + Sync
+    {
+        ranked.truncate(k.max(1));
+        ranked.par_iter()
+            .map(|cont| {
+                let mut member = cont.member.clone();
+                (prob.solve_full(&mut member), Arc::new(member))
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .fold(None, |top: Option<(f32, Arc<T>)>, curr| match top {
+                Some(t) if t.0 >= curr.0 => Some(t),
+                _ => Some(curr)
+            })
+    }
+
+    /// Get the top member of the generation by the feasibility rules - the feasible member with the
+    /// highest objective, or if there are none the member which violates its constraints the least.
+    /// The objective is returned with the member, not the fitness score used for selection
+    #[inline]
+    pub fn best_feasible(&self) -> Option<(f32, Arc<T>)> {
+        let top = self.members.iter().filter(|x| !x.predicted).max_by(|a, b| {
+            match (a.is_feasible(), b.is_feasible()) {
+                (true, true) => a.objective.partial_cmp(&b.objective).unwrap(),
+                (false, false) => b.violation.partial_cmp(&a.violation).unwrap(),
+                (one, _) => one.cmp(&!one)
+            }
+        })?;
+        Some((top.objective, Arc::new(top.member.clone())))
+    }
 }
@@ -1,16 +1,14 @@
-/// Provide options for cleaning up the population or applying 
-/// some sort of natural selection over the population through time
+//! Provide options for cleaning up the population or applying 
+//! some sort of natural selection over the population through time
 
 extern crate rayon;
 extern crate rand;  
 
 use std::marker::Sync;
-use std::sync::{Arc};
 use rayon::prelude::*;
 use rand::Rng;
 use super::generation::{Generation};
 use super::genome::{Genome};
-use super::niche::{NicheMember};
 
 
 
@@ -58,9 +56,7 @@ impl Genocide {
             let to_remove = generation.species.len() - num; 
             generation.species
                 .sort_by(|a, b| {
-                    let a_age = a.read().unwrap().age;
-                    let b_age = b.read().unwrap().age;
-                    a_age.partial_cmp(&b_age).unwrap()
+                    a.age.partial_cmp(&b.age).unwrap()
                 });
             generation.species.truncate(to_remove);
         }
@@ -77,20 +73,16 @@ impl Genocide {
     {
         generation.species
             .par_iter_mut()
-            .map_init(|| rand::thread_rng(), |r, spec| {
-                let mut new_members = Vec::new();
-                for mem in spec.read().unwrap().members.iter() {
-                    if r.gen::<f32>() > perc {
-                        let solid_member = mem.1.upgrade().unwrap();
-                        let copy_member = NicheMember(mem.0, Arc::downgrade(&solid_member));
-                        new_members.push(copy_member);
-                    }
+            .for_each_init(rand::thread_rng, |r, spec| {
+                let new_members = spec.members
+                    .iter()
+                    .filter(|_| r.gen::<f32>() > perc)
+                    .copied()
+                    .collect::<Vec<_>>();
+                if !new_members.is_empty() {
+                    spec.members = new_members;
                 }
-                if new_members.len() > 0 {
-                    spec.write().unwrap().members = new_members;
-                }
-            })
-            .collect::<Vec<_>>();
+            });
     }
 
 
@@ -105,19 +97,16 @@ impl Genocide {
     {
         generation.species 
             .par_iter_mut()
-            .map(|spec| {
-                let size = spec.read().unwrap().members.len();
+            .for_each(|spec| {
+                let size = spec.members.len();
                 let num_to_remove = size as f32 * perc;
-                spec.write().unwrap()
-                    .members
+                spec.members
                     .sort_by(|a, b| {
                         b.0.partial_cmp(&a.0).unwrap()
                     });
-                spec.write().unwrap()
-                    .members
+                spec.members
                     .truncate(size - num_to_remove as usize);
-            })
-            .collect::<Vec<_>>();
+            });
     }
 
 
@@ -131,8 +120,8 @@ impl Genocide {
     {
         generation.species
             .sort_by(|a, b| {
-                let a_fit = a.write().unwrap().get_total_adjusted_fitness();
-                let b_fit = b.write().unwrap().get_total_adjusted_fitness();
+                let a_fit = a.get_total_adjusted_fitness();
+                let b_fit = b.get_total_adjusted_fitness();
                 b_fit.partial_cmp(&a_fit).unwrap()
             });
         generation.species.truncate(num);
@@ -0,0 +1,102 @@
+//! The hall of fame keeps copies of the best members seen over the whole run,
+//! not just the best of the current generation. When the problem changes the
+//! members in it are re-scored so it always reflects the current problem.
+
+use super::{
+    genome::Genome,
+    problem::Problem,
+    generation::Generation,
+    genealogy::MemberId
+};
+
+
+
+/// A member in the hall of fame, its id from its lineage, its fitness, and a copy of the member
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Famer<T> {
+    pub id: MemberId,
+    pub fitness: f32,
+    pub member: T
+}
+
+
+
+/// The best members seen over the run, sorted from best to worst
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HallOfFame<T> {
+    pub capacity: usize,
+    pub members: Vec<Famer<T>>
+}
+
+
+
+impl<T> HallOfFame<T>
+    where T: Clone
+{
+
+    /// an empty hall of fame which holds up to capacity members
+    pub fn new(capacity: usize) -> Self {
+        HallOfFame {
+            capacity,
+            members: Vec::new()
+        }
+    }
+
+    /// the best member in the hall of fame
+    pub fn best(&self) -> Option<&Famer<T>> {
+        self.members.first()
+    }
+
+    /// the number of members in the hall of fame
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// true if the hall of fame is empty
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// offer a member to the hall of fame, it is only kept if it is one of the best seen.
+    /// A member already in the hall of fame has its fitness updated
+    pub fn offer(&mut self, id: MemberId, fitness: f32, member: &T) {
+        if self.capacity == 0 {
+            return;
+        }
+        if let Some(famer) = self.members.iter_mut().find(|x| x.id == id) {
+            famer.fitness = fitness;
+        } else if self.members.len() < self.capacity || self.members.last().is_some_and(|x| fitness > x.fitness) {
+            self.members.push(Famer { id, fitness, member: member.clone() });
+        }
+        self.sort();
+    }
+
+    /// offer every feasible member of an optimized generation to the hall of fame by the problem's fitness,
+    /// not the score constraint handling or parsimony gave it for selection. Members whose fitness was only
+    /// predicted by a surrogate model are left out
+    pub fn update<E>(&mut self, gen: &Generation<T, E>)
+        where
+            T: Genome<T, E> + Send + Sync,
+            E: Send + Sync
+    {
+        for cont in gen.members.iter().filter(|x| !x.predicted && x.is_feasible()) {
+            self.offer(cont.lineage.id, cont.objective, &cont.member);
+        }
+    }
+
+    /// score every member again on everything the problem has, this needs to happen whenever the problem changes
+    pub fn rescore<P>(&mut self, problem: &P)
+        where P: Problem<T>
+    {
+        for famer in self.members.iter_mut() {
+            famer.fitness = problem.solve_full(&mut famer.member);
+        }
+        self.sort();
+    }
+
+    /// keep the members sorted best to worst and no more than the capacity
+    fn sort(&mut self) {
+        self.members.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap());
+        self.members.truncate(self.capacity);
+    }
+}
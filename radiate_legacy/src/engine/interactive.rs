@@ -0,0 +1,114 @@
+//! Interactive evolution, where the fitness of a member comes from a person rating it instead of
+//! a function. The population hands out the members of the current generation as candidates, and
+//! takes back ratings for as many of them as the person got to. Members left unrated are filled in -
+//! with the mean or worst rating given, or by the population's surrogate model fit on every rating
+//! so far - then the generation is finished and the next one is bred and waits to be rated.
+
+use uuid::Uuid;
+use super::{
+    genome::Genome,
+    generation::Container,
+    genealogy::MemberId,
+    problem::Problem,
+    surrogate::Surrogate
+};
+
+
+
+/// A member of the current generation waiting to be rated
+///
+/// index: where the member is in the generation
+/// id: the member's id from its lineage, ratings are given back by id
+/// species: the species the member was bred in, None in the first generation
+/// member: the member itself
+#[derive(Debug, Clone)]
+pub struct Candidate<'a, T> {
+    pub index: usize,
+    pub id: MemberId,
+    pub species: Option<Uuid>,
+    pub member: &'a T
+}
+
+
+
+/// How members which weren't rated get their fitness
+///
+/// Mean - the mean of the ratings given this generation, a member rated twice only counts its last rating
+/// Worst - the lowest rating given this generation, a member rated twice only counts its last rating
+/// Model - predicted by the population's surrogate model, which learns from every rating given.
+///         Without a surrogate, or before it has learned anything, this falls back to the mean
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FillIn {
+    Mean,
+    Worst,
+    Model
+}
+
+
+
+/// The problem of a population whose fitness only comes from ratings. It is never asked to
+/// solve a member while the population is rated, if it is it gives every member 0
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Rated;
+
+
+
+impl<T> Problem<T> for Rated {
+
+    fn empty() -> Self { Rated }
+
+    fn solve(&self, _: &mut T) -> f32 {
+        0.0
+    }
+}
+
+
+
+impl FillIn {
+
+    /// Give every member its rating, members without one are marked as predicted and filled in. The
+    /// surrogate learns from the ratings before it fills anyone in. Errors if nobody was rated or a
+    /// rating is for a member which isn't in the generation
+    pub fn apply<T, E>(&self, members: &mut [Container<T, E>], ratings: &[(MemberId, f32)], mut surrogate: Option<&mut Surrogate<T>>) -> Result<(), &'static str>
+        where
+            T: Genome<T, E> + Send + Sync,
+            E: Send + Sync
+    {
+        if ratings.is_empty() {
+            return Err("No ratings given");
+        }
+        // check every rating before touching the members so an error leaves them as they were.
+        // A member rated more than once keeps the last rating it was given
+        let mut rated = vec![None; members.len()];
+        for (id, rating) in ratings.iter() {
+            let index = members.iter()
+                .position(|x| x.lineage.id == *id)
+                .ok_or("Rating for a member not in the generation")?;
+            rated[index] = Some(*rating);
+        }
+        for (cont, rating) in members.iter_mut().zip(rated.iter().cloned()) {
+            cont.predicted = rating.is_none();
+            if let Some(rating) = rating {
+                cont.fitness_score = rating;
+                cont.objective = rating;
+            }
+        }
+        if let Some(surrogate) = surrogate.as_mut() { surrogate.learn(members); }
+        // fill in from the rating each member ended up with, not every rating given
+        let given = rated.iter().flatten().cloned().collect::<Vec<_>>();
+        let mean = given.iter().sum::<f32>() / given.len() as f32;
+        let worst = given.iter().cloned().fold(f32::MAX, f32::min);
+        for cont in members.iter_mut().filter(|x| x.predicted) {
+            cont.fitness_score = match self {
+                FillIn::Mean => mean,
+                FillIn::Worst => worst,
+                FillIn::Model => surrogate
+                    .as_ref()
+                    .and_then(|x| x.predict_member(&cont.member))
+                    .unwrap_or(mean)
+            };
+            cont.objective = cont.fitness_score;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+//! A structured log of a run. Where debug prints the species of every generation to the
+//! console, the run logger writes one JSON object per generation (JSON lines) to a file or
+//! anything else which can be written to, so the run can be loaded back later for plotting
+//! or compared against other runs with the run log reader.
+
+extern crate serde_json;
+
+use std::fs::File;
+use std::io::{self, Write, BufRead, BufReader, BufWriter};
+use uuid::Uuid;
+use super::{
+    genome::Genome,
+    generation::Generation,
+    genealogy::MemberId,
+    population::Config,
+    stats::GenerationStats,
+    report::SpeciesSummary
+};
+
+
+
+/// The best member of a generation as it shows up in the log
+///
+/// fitness: the fitness the population reported for it
+/// id: the member's id from its lineage
+/// species: the species the member is in
+/// age: the number of generations the member's genetic material has been evolving
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChampionSummary {
+    pub fitness: f32,
+    pub id: MemberId,
+    pub species: Option<Uuid>,
+    pub age: usize
+}
+
+
+
+/// A single line of the log, everything about one generation
+///
+/// stats: the summary stats of the generation
+/// species: the species table of the generation
+/// champion: the best member of the generation, None if it couldn't be found among the members
+/// config: the speciation config the generation was speciated with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub stats: GenerationStats,
+    pub species: Vec<SpeciesSummary>,
+    pub champion: Option<ChampionSummary>,
+    pub config: Config
+}
+
+
+
+/// Writes a log entry per generation as a line of JSON. The first error writing to the
+/// log is kept and nothing more is written after it, training carries on either way
+pub struct RunLogger {
+    writer: Box<dyn Write + Send + Sync>,
+    lines: usize,
+    error: Option<io::Error>
+}
+
+
+
+/// A run log read back in, the entries in the order they were written
+#[derive(Debug, Clone, Default)]
+pub struct RunLog {
+    pub entries: Vec<LogEntry>
+}
+
+
+
+impl ChampionSummary {
+
+    /// find the champion among the members of the generations, the first member equal to the top member
+    pub fn find<T, E>(gens: &[&Generation<T, E>], fitness: f32, top: &T) -> Option<Self>
+        where
+            T: Genome<T, E> + Send + Sync + PartialEq,
+            E: Send + Sync
+    {
+        gens.iter()
+            .flat_map(|gen| gen.members.iter())
+            .find(|x| !x.predicted && x.member == *top)
+            .map(|x| ChampionSummary {
+                fitness,
+                id: x.lineage.id,
+                species: x.species,
+                age: x.age
+            })
+    }
+}
+
+
+
+impl RunLogger {
+
+    /// log to anything which can be written to
+    pub fn new<W>(writer: W) -> Self
+        where W: Write + Send + Sync + 'static
+    {
+        RunLogger {
+            writer: Box::new(writer),
+            lines: 0,
+            error: None
+        }
+    }
+
+    /// log to a file, creating it or truncating it if it already exists
+    pub fn to_file(file_path: &str) -> io::Result<Self> {
+        Ok(RunLogger::new(BufWriter::new(File::create(file_path)?)))
+    }
+
+    /// write an entry as a line of the log, the line is flushed so the log can be read while the run goes on
+    pub fn log(&mut self, entry: &LogEntry) {
+        if self.error.is_some() {
+            return;
+        }
+        let result = serde_json::to_writer(&mut self.writer, entry)
+            .map_err(io::Error::from)
+            .and_then(|_| self.writer.write_all(b"\n"))
+            .and_then(|_| self.writer.flush());
+        match result {
+            Ok(_) => self.lines += 1,
+            Err(e) => self.error = Some(e)
+        }
+    }
+
+    /// the number of entries written
+    pub fn lines(&self) -> usize {
+        self.lines
+    }
+
+    /// the error which stopped the logger if there was one
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+}
+
+
+
+impl RunLog {
+
+    /// read a log from anything which can be read line by line, blank lines are skipped
+    pub fn from_reader<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut entries = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            entries.push(serde_json::from_str(&line)?);
+        }
+        Ok(RunLog { entries })
+    }
+
+    /// read a log written to a file
+    pub fn load(file_path: &str) -> io::Result<Self> {
+        RunLog::from_reader(BufReader::new(File::open(file_path)?))
+    }
+
+    /// the number of generations in the log
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// true if the log is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// the best fitness of every generation
+    pub fn fitness_curve(&self) -> Vec<f32> {
+        self.entries.iter().map(|x| x.stats.best).collect()
+    }
+
+    /// the mean fitness of every generation
+    pub fn mean_curve(&self) -> Vec<f32> {
+        self.entries.iter().map(|x| x.stats.mean).collect()
+    }
+
+    /// the number of species in every generation
+    pub fn species_counts(&self) -> Vec<usize> {
+        self.entries.iter().map(|x| x.species.len()).collect()
+    }
+
+    /// the first generation whose best fitness reached the target, None if it never did
+    pub fn generations_to(&self, target: f32) -> Option<usize> {
+        self.entries
+            .iter()
+            .find(|x| x.stats.best >= target)
+            .map(|x| x.stats.generation)
+    }
+}
@@ -0,0 +1,449 @@
+//! MAP-Elites quality diversity search. Instead of evolving toward one champion, the space
+//! of behaviours is split into cells and the archive keeps the best member (the elite) found
+//! for each cell. Parents are picked uniformly from the occupied cells and their children only
+//! replace an elite if they land in its cell and score better, so the end result is a library
+//! of diverse, high performing solutions.
+
+extern crate rayon;
+extern crate rand;
+extern crate serde_json;
+
+use std::fs::File;
+use std::error::Error;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use rayon::prelude::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use super::{
+    genome::Genome,
+    environment::Envionment,
+    behavior::Behavior,
+    operator::Pipeline,
+    generation::{Generation, BREED_ATTEMPTS}
+};
+
+
+
+/// How the behaviour space is split into cells
+///
+/// Grid - an even grid, the number of bins and the (lower, upper) bounds of each dimension
+///        of the behaviour descriptor. Descriptors outside of the bounds are put in the edge cells
+/// Cvt - a centroidal voronoi tessellation, each cell is the area closest to one of the centroids.
+///       This scales to many more dimensions than a grid does
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Tessellation {
+    Grid(Vec<usize>, Vec<(f32, f32)>),
+    Cvt(Vec<Vec<f32>>)
+}
+
+
+
+/// The best member found for a cell along with its fitness and behaviour descriptor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Elite<T> {
+    pub member: T,
+    pub fitness: f32,
+    pub descriptor: Vec<f32>
+}
+
+
+
+/// The archive of elites, keyed by the index of the cell they are in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Archive<T> {
+    pub tessellation: Tessellation,
+    pub elites: BTreeMap<usize, Elite<T>>
+}
+
+
+
+/// MapElites is the driver for the search, it is set up and run the same way a population is
+pub struct MapElites<T, E, P>
+    where
+        T: Genome<T, E> + Send + Sync,
+        E: Envionment + Sized + Send + Sync,
+        P: Behavior<T>
+{
+    initial: usize,
+    batch: usize,
+    crossover_rate: f32,
+    debug_progress: bool,
+    archive: Archive<T>,
+    solve: Arc<RwLock<P>>,
+    environment: Arc<RwLock<E>>,
+    operators: Option<Arc<Pipeline<T, E>>>,
+    repairs: usize,
+    discards: usize
+}
+
+
+
+impl Tessellation {
+
+    /// an even grid with a number of bins for each dimension within the (lower, upper) bounds of each dimension
+    pub fn grid(bins: Vec<usize>, bounds: Vec<(f32, f32)>) -> Self {
+        Tessellation::Grid(bins, bounds)
+    }
+
+    /// Build a centroidal voronoi tessellation with a number of cells within the bounds of each dimension.
+    /// The centroids are found by running k-means over random samples of the behaviour space. A dimension
+    /// with the same lower and upper bound is flat, every centroid sits at that value
+    pub fn cvt(cells: usize, bounds: Vec<(f32, f32)>, samples: usize) -> Result<Self, &'static str> {
+        if cells == 0 {
+            return Err("Tessellation needs at least one cell");
+        }
+        if bounds.iter().any(|(low, high)| low.is_nan() || high.is_nan() || low > high) {
+            return Err("Bounds need a lower bound at or below their upper bound");
+        }
+        let mut r = rand::thread_rng();
+        let mut sample = || bounds.iter()
+            .map(|(low, high)| if low < high { r.gen_range(*low, *high) } else { *low })
+            .collect::<Vec<_>>();
+        let points = (0..samples.max(cells)).map(|_| sample()).collect::<Vec<_>>();
+        let mut centroids = points.iter().take(cells).cloned().collect::<Vec<_>>();
+        for _ in 0..20 {
+            // assign every point to its closest centroid then move the centroids to the middle of their points
+            let mut sums = vec![vec![0.0; bounds.len()]; cells];
+            let mut counts = vec![0; cells];
+            for point in points.iter() {
+                let closest = Tessellation::closest(&centroids, point);
+                counts[closest] += 1;
+                for (sum, value) in sums[closest].iter_mut().zip(point.iter()) {
+                    *sum += value;
+                }
+            }
+            for ((centroid, sum), count) in centroids.iter_mut().zip(sums).zip(counts) {
+                if count > 0 {
+                    *centroid = sum.into_iter().map(|x| x / count as f32).collect();
+                }
+            }
+        }
+        Ok(Tessellation::Cvt(centroids))
+    }
+
+    /// the total number of cells in the tessellation
+    pub fn cells(&self) -> usize {
+        match self {
+            Self::Grid(bins, _) => bins.iter().product(),
+            Self::Cvt(centroids) => centroids.len()
+        }
+    }
+
+    /// Find the index of the cell a behaviour descriptor falls in, if the descriptor
+    /// doesn't have the same number of dimensions as the tessellation None is returned
+    pub fn cell(&self, descriptor: &[f32]) -> Option<usize> {
+        match self {
+            Self::Grid(bins, bounds) => {
+                if descriptor.len() != bins.len() || bins.is_empty() || bins.contains(&0) {
+                    return None;
+                }
+                let mut index = 0;
+                for ((value, bin), (low, high)) in descriptor.iter().zip(bins.iter()).zip(bounds.iter()) {
+                    let scaled = ((value - low) / (high - low) * *bin as f32).floor();
+                    let position = (scaled.max(0.0) as usize).min(bin - 1);
+                    index = index * bin + position;
+                }
+                Some(index)
+            },
+            Self::Cvt(centroids) => {
+                if centroids.is_empty() || centroids[0].len() != descriptor.len() {
+                    return None;
+                }
+                Some(Tessellation::closest(centroids, descriptor))
+            }
+        }
+    }
+
+    /// the index of the centroid closest to the point
+    fn closest(centroids: &[Vec<f32>], point: &[f32]) -> usize {
+        let distance = |centroid: &Vec<f32>| centroid.iter()
+            .zip(point.iter())
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f32>();
+        centroids.iter()
+            .enumerate()
+            .min_by(|a, b| distance(a.1).partial_cmp(&distance(b.1)).unwrap())
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    }
+}
+
+
+
+impl<T> Archive<T> {
+
+    /// an empty archive over a tessellation
+    pub fn new(tessellation: Tessellation) -> Self {
+        Archive {
+            tessellation,
+            elites: BTreeMap::new()
+        }
+    }
+
+    /// Try to put a member in the archive, it is only added if its cell is empty or it scores
+    /// better than the elite already there. Returns true if the member was added
+    pub fn insert(&mut self, member: T, fitness: f32, descriptor: Vec<f32>) -> bool {
+        let cell = match self.tessellation.cell(&descriptor) {
+            Some(cell) => cell,
+            None => return false
+        };
+        match self.elites.get(&cell) {
+            Some(elite) if elite.fitness >= fitness => false,
+            _ => {
+                self.elites.insert(cell, Elite { member, fitness, descriptor });
+                true
+            }
+        }
+    }
+
+    /// the number of occupied cells
+    pub fn len(&self) -> usize {
+        self.elites.len()
+    }
+
+    /// true if no cells are occupied
+    pub fn is_empty(&self) -> bool {
+        self.elites.is_empty()
+    }
+
+    /// the fraction of cells which are occupied
+    pub fn coverage(&self) -> f32 {
+        self.elites.len() as f32 / self.tessellation.cells().max(1) as f32
+    }
+
+    /// The QD-score, the sum of the fitness of every elite. This assumes fitness
+    /// is never negative, otherwise filling a new cell could lower the score
+    pub fn qd_score(&self) -> f32 {
+        self.elites.values().map(|x| x.fitness).sum()
+    }
+
+    /// the best elite in the archive
+    pub fn best(&self) -> Option<&Elite<T>> {
+        self.elites
+            .values()
+            .max_by(|a, b| a.fitness.partial_cmp(&b.fitness).unwrap())
+    }
+
+    /// save the archive as json to a file path
+    pub fn save(&self, file_path: &str) -> Result<(), Box<dyn Error>>
+        where T: Serialize
+    {
+        serde_json::to_writer(&File::create(file_path)?, &self)?;
+        Ok(())
+    }
+
+    /// load an archive from a json file
+    pub fn load(file_path: &str) -> Result<Self, Box<dyn Error>>
+        where T: DeserializeOwned
+    {
+        Ok(serde_json::from_reader(File::open(file_path)?)?)
+    }
+}
+
+
+
+/// implement map elites
+impl<T, E, P> MapElites<T, E, P>
+    where
+        T: Genome<T, E> + Clone + Send + Sync,
+        E: Envionment + Sized + Send + Sync + Default,
+        P: Behavior<T> + Send + Sync
+{
+
+    /// base map elites settings
+    pub fn new() -> Self {
+        MapElites {
+            // the number of random members from the genome's base function to start the archive with
+            initial: 100,
+            // the number of children created and evaluated every generation
+            batch: 100,
+            // chance a child is made by crossover instead of mutation if no operators are given
+            crossover_rate: 0.5,
+            // print the state of the archive to the screen
+            debug_progress: false,
+            // the archive needs a tessellation to have any cells
+            archive: Archive::new(Tessellation::Grid(Vec::new(), Vec::new())),
+            // the problem to solve, shared between threads the same way the population does it
+            solve: Arc::new(RwLock::new(P::empty())),
+            environment: Arc::new(RwLock::new(E::default())),
+            // optional pipeline of operators, if none is given the genome's crossover is used
+            operators: None,
+            // the number of children of the last generation which were invalid and repaired or thrown away
+            repairs: 0,
+            discards: 0
+        }
+    }
+
+    /// Run one generation of the search. The first generation fills the archive with random
+    /// members, every one after picks parents uniformly from the occupied cells, creates a batch
+    /// of children, evaluates them in parallel, and tries to add them to the archive.
+    /// Children are checked the same way a population checks them, an invalid child is repaired
+    /// if it can be, otherwise it is thrown away and bred again from new parents.
+    /// Returns the number of children which made it into the archive
+    pub fn train(&mut self) -> Option<usize> {
+        let (repairs, discards) = (AtomicUsize::new(0), AtomicUsize::new(0));
+        let mut members = if self.archive.is_empty() {
+            let mut lock_set = self.environment.write().ok()?;
+            (0..self.initial)
+                .map(|_| T::base(&mut lock_set))
+                .collect::<Vec<_>>()
+        } else {
+            let elites = self.archive.elites.values().collect::<Vec<_>>();
+            (0..self.batch)
+                .into_par_iter()
+                .map_init(rand::thread_rng, |r, _| {
+                    let mut attempts = 0;
+                    loop {
+                        attempts += 1;
+                        let one = &elites.choose(r)?.member;
+                        let two = &elites.choose(r)?.member;
+                        let child = match &self.operators {
+                            Some(pipeline) => pipeline.apply(one, two, Arc::clone(&self.environment)),
+                            None => T::crossover(one, two, Arc::clone(&self.environment), self.crossover_rate)
+                        };
+                        // the same as a population, a child which couldn't be made is bred again
+                        // and after too many attempts the child is a copy of its first parent
+                        let child = match child {
+                            Some(child) => child,
+                            None if attempts < BREED_ATTEMPTS => continue,
+                            None => return Some(one.clone())
+                        };
+                        match Generation::check(child, &self.environment) {
+                            Ok(child) => return Some(child),
+                            Err(Some(fixed)) => {
+                                repairs.fetch_add(1, Ordering::Relaxed);
+                                return Some(fixed);
+                            },
+                            Err(None) => {
+                                discards.fetch_add(1, Ordering::Relaxed);
+                                if attempts >= BREED_ATTEMPTS {
+                                    return Some(one.clone());
+                                }
+                            }
+                        }
+                    }
+                })
+                .collect::<Option<Vec<_>>>()?
+        };
+        self.repairs = repairs.into_inner();
+        self.discards = discards.into_inner();
+        let scores = members
+            .par_iter_mut()
+            .map(|member| self.solve.read().unwrap().evaluate(member))
+            .collect::<Vec<_>>();
+        let added = members.into_iter()
+            .zip(scores)
+            .map(|(member, (fitness, descriptor))| self.archive.insert(member, fitness, descriptor))
+            .filter(|added| *added)
+            .count();
+        Some(added)
+    }
+
+    /// Run the search according to a user defined function, the inputs of which are the
+    /// archive and the current number of generations. This will continue until the function
+    /// returns true, then the archive is returned
+    pub fn run<F>(&mut self, runner: F) -> Result<Archive<T>, &'static str>
+        where F: Fn(&Archive<T>, i32) -> bool + Sized
+    {
+        if self.archive.tessellation.cells() == 0 {
+            return Err("Archive has no cells");
+        }
+        let mut index = 0;
+        loop {
+            let added = self.train().ok_or("Error Training")?;
+            if self.debug_progress {
+                println!("gen: {} added: {} coverage: {:.3} qd score: {:.3} repairs: {} discards: {}", index, added, self.archive.coverage(), self.archive.qd_score(), self.repairs, self.discards);
+            }
+            if runner(&self.archive, index) {
+                return Ok(self.archive.clone());
+            }
+            index += 1;
+        }
+    }
+
+    /// get the archive of elites
+    pub fn archive(&self) -> &Archive<T> {
+        &self.archive
+    }
+
+    /// the number of children bred in the last generation which were invalid and had to be repaired
+    pub fn repairs(&self) -> usize {
+        self.repairs
+    }
+
+    /// the number of children bred in the last generation which were invalid and thrown away
+    pub fn discards(&self) -> usize {
+        self.discards
+    }
+
+    /// set how the behaviour space is split into cells, this empties the archive
+    pub fn tessellation(mut self, tessellation: Tessellation) -> Self {
+        self.archive = Archive::new(tessellation);
+        self
+    }
+
+    /// start from an existing archive, for example one loaded from a file
+    pub fn resume(mut self, archive: Archive<T>) -> Self {
+        self.archive = archive;
+        self
+    }
+
+    /// the number of random members to start the archive with
+    pub fn initial(mut self, initial: usize) -> Self {
+        self.initial = initial;
+        self
+    }
+
+    /// the number of children created each generation
+    pub fn batch(mut self, batch: usize) -> Self {
+        self.batch = batch;
+        self
+    }
+
+    /// the chance of a child being made by crossover instead of mutation, only used if there are no operators
+    pub fn crossover_rate(mut self, rate: f32) -> Self {
+        self.crossover_rate = rate;
+        self
+    }
+
+    /// give the search a pipeline of operators to create children with
+    pub fn operators(mut self, pipeline: Pipeline<T, E>) -> Self {
+        self.operators = Some(Arc::new(pipeline));
+        self
+    }
+
+    /// give settings to the search for the genome to evolve by
+    pub fn constrain(mut self, environment: E) -> Self {
+        self.environment = Arc::new(RwLock::new(environment));
+        self
+    }
+
+    /// give the search a problem to solve
+    pub fn impose(mut self, prob: P) -> Self {
+        self.solve = Arc::new(RwLock::new(prob));
+        self
+    }
+
+    /// print the state of the archive every generation
+    pub fn debug(mut self, d: bool) -> Self {
+        self.debug_progress = d;
+        self
+    }
+}
+
+
+
+impl<T, E, P> Default for MapElites<T, E, P>
+    where
+        T: Genome<T, E> + Clone + Send + Sync,
+        E: Envionment + Sized + Send + Sync + Default,
+        P: Behavior<T> + Send + Sync
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
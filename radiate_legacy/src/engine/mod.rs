@@ -3,6 +3,28 @@ pub mod generation;
 pub mod niche;
 pub mod genocide;
 pub mod survival;
+pub mod operator;
+pub mod genealogy;
+pub mod alps;
+pub mod mapelites;
+pub mod sweep;
+pub mod stats;
+pub mod halloffame;
+pub mod dataset;
+pub mod constraint;
+pub mod parsimony;
+pub mod surrogate;
+pub mod distance;
+pub mod report;
+pub mod logger;
+pub mod experiment;
+pub mod sizing;
+pub mod probes;
+pub mod interactive;
+pub mod diagnostics;
+pub mod random;
+pub mod cmaes;
+pub mod differential;
 
 
 
@@ -34,7 +56,55 @@ pub mod genome {
         /// a measure of how far away two types are from each other in a genetic 
         /// sense. Think of something like how similar humans are to dolphins, this is a way to quantify that.
         fn distance(one: &T, two: &T, env: Arc<RwLock<E>>) -> f32;
-        
+
+        /// Recombine is only the crossover half of crossover - parts of one type are given to parts
+        /// of the other and the resulting type is returned without being mutated. This is used by an 
+        /// operator pipeline so that a child can be both crossed over and mutated.
+        /// 
+        /// The default is crossover with the crossover rate forced to 1.0, which for genomes 
+        /// that flip a coin on the crossover rate is exactly their crossover branch
+        fn recombine(one: &T, two: &T, env: Arc<RwLock<E>>) -> Option<T>
+            where
+                T: Sized,
+                E: Envionment + Sized
+        {
+            Self::crossover(one, two, env, 1.0)
+        }
+
+        /// Mutate is only the mutation half of crossover - a mutated copy of the given type is returned.
+        /// 
+        /// The default is crossover of the type with itself with the crossover rate forced to 0.0, which
+        /// for genomes that flip a coin on the crossover rate is exactly their mutation branch
+        fn mutate(member: &T, env: Arc<RwLock<E>>) -> Option<T>
+            where
+                T: Sized,
+                E: Envionment + Sized
+        {
+            Self::crossover(member, member, env, 0.0)
+        }
+
+        /// The size of the member's structure - for a network it's something like the number of nodes
+        /// and edges, for a tree the number of nodes. This is what parsimony pressure pushes down to keep
+        /// members from growing without bound. The default is 0.0 so every member is the same size
+        fn complexity(_member: &T) -> f32 {
+            0.0
+        }
+
+        /// Check a member is valid - something like a tree being no taller than its max height. Every child
+        /// is checked right after it is bred, one which isn't valid is repaired or thrown away and bred again.
+        /// The default is that every member is valid
+        fn validate(_member: &T, _env: Arc<RwLock<E>>) -> bool {
+            true
+        }
+
+        /// Fix an invalid member, returning the fixed member or None if it can't be fixed and should be
+        /// thrown away. The repaired member is checked again. The default can't fix anything
+        fn repair(_member: &T, _env: Arc<RwLock<E>>) -> Option<T>
+            where T: Sized
+        {
+            None
+        }
+
         /// Genome needs to have a base implementation in order for one of the population options to be satisfied
         /// 
         /// This can probably be implemented in a generic way for default if the user doesn't want to
@@ -61,8 +131,26 @@ pub mod environment {
         /// Reset can be used to reset the environment after a certain event occurs,
         /// if not this is an empty default implementation
         fn reset(&mut self) { }
+
+        /// Adapt the environment's mutation settings given the fraction of children bred with it
+        /// that beat their fittest parent. This is only called on the copies of the environment
+        /// each species gets when the population has adaptive species, the default leaves it alone
+        fn adapt(&mut self, _success_rate: f32) { }
     
     }
+
+    /// Rechenberg's 1/5th success rule - if more than a fifth of the children beat their parents
+    /// the steps are too small and should grow, if less than a fifth they should shrink.
+    /// Returns the factor to scale the step size by
+    pub fn one_fifth(success_rate: f32) -> f32 {
+        if success_rate > 0.2 {
+            1.0 / 0.85
+        } else if success_rate < 0.2 {
+            0.85
+        } else {
+            1.0
+        }
+    }
 }
 
 
@@ -78,6 +166,13 @@ pub mod environment {
 /// is explicitly readonly 
 pub mod problem {
 
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use rand::rngs::StdRng;
+    use uuid::Uuid;
+    use super::genealogy::MemberId;
+    use super::stats::GenerationStats;
+
     pub trait Problem<T> {
 
         /// empty can be a new for Self, or some sort of default value,
@@ -88,5 +183,112 @@ pub mod problem {
         /// use the data in the type implementing the problem to solve the problem and return
         /// the member's score. The result of this function is the member's fitness score 
         fn solve(&self, member: &mut T) -> f32;
+
+        /// Solve the problem knowing where the member is in the run - the generation, the member's id
+        /// and species, a random number generator seeded for this member, and whether the run has been
+        /// cancelled. This is what the population calls, by default it just calls solve
+        fn solve_with_context(&self, member: &mut T, _context: &mut EvalContext) -> f32 {
+            self.solve(member)
+        }
+
+        /// Called between generations with the stats of the generation which was just solved, this is
+        /// where a curriculum or non-stationary problem changes itself. Return true if the problem changed,
+        /// the population will then re-score its hall of fame against the new problem
+        fn advance(&mut self, _stats: &GenerationStats) -> bool {
+            false
+        }
+
+        /// Solve the problem on everything it has. Problems which only solve on part of their data
+        /// each generation (like a mini-batch) use this to double check the best members before they
+        /// are reported, by default it just calls solve
+        fn solve_full(&self, member: &mut T) -> f32 {
+            self.solve(member)
+        }
+
+        /// How much the member violates the problem's hard constraints, kept separate from its fitness.
+        /// 0.0 means the member is feasible, anything above is the total amount of violation. Only
+        /// used if the population is given a constraint handling, by default every member is feasible
+        fn constraint_violation(&self, _member: &T) -> f32 {
+            0.0
+        }
     }
-}
\ No newline at end of file
+
+
+    /// Everything the population knows about a member when it asks for it to be solved
+    ///
+    /// generation: the index of the current generation
+    /// index: the index of the member in the current generation
+    /// id: the member's id from its lineage, stable for as long as the member survives
+    /// species: the species the member was in last generation, None for new members
+    /// rng: a random number generator seeded from the population's seed, the generation, and the member's index
+    ///      so any noise used to solve the problem is reproducible
+    /// cancel: set when the run has been cancelled, long running problems can check it to stop early
+    #[derive(Debug, Clone)]
+    pub struct EvalContext {
+        pub generation: usize,
+        pub index: usize,
+        pub id: MemberId,
+        pub species: Option<Uuid>,
+        pub rng: StdRng,
+        pub cancel: Arc<AtomicBool>
+    }
+
+
+    impl EvalContext {
+
+        /// true if the run has been cancelled
+        pub fn cancelled(&self) -> bool {
+            self.cancel.load(Ordering::Relaxed)
+        }
+    }
+}
+
+
+
+/// Behavior is a problem for quality diversity search. Instead of only scoring a member it also
+/// describes how the member behaves as a vec of numbers (the behaviour descriptor), so the search
+/// can keep the best member for every kind of behaviour instead of one champion
+pub mod behavior {
+
+    pub trait Behavior<T> {
+
+        /// empty can be a new for Self, or some sort of default value
+        fn empty() -> Self;
+
+        /// Evaluate the member, returning its fitness score and its behaviour descriptor.
+        /// The descriptor should always be the same length and within the bounds given to the archive
+        fn evaluate(&self, member: &mut T) -> (f32, Vec<f32>);
+    }
+}
+
+
+
+/// RealValued is a genome which is nothing more than a fixed length vector of real numbers.
+/// Continuous optimizers like CMA-ES or differential evolution don't need crossover or distance,
+/// they only need to be able to read the numbers out of a member and build a new member from a 
+/// set of numbers, so the problem can be solved with the exact same Problem trait used by the population
+pub mod realvalued {
+
+    pub trait RealValued {
+
+        /// create a new member from a vec of real numbers, this is called for 
+        /// every candidate an optimizer samples so it should be cheap
+        fn from_values(values: Vec<f32>) -> Self;
+
+        /// get the real numbers which make up this member 
+        fn values(&self) -> &[f32];
+    }
+
+
+    /// A plain vec of floats is the simplest real valued genome there is
+    impl RealValued for Vec<f32> {
+
+        fn from_values(values: Vec<f32>) -> Self {
+            values
+        }
+
+        fn values(&self) -> &[f32] {
+            &self[..]
+        }
+    }
+}
@@ -6,31 +6,35 @@ use std::sync::{Arc, RwLock};
 use std::marker::PhantomData;
 use uuid::Uuid;
 use rand::prelude::SliceRandom;
-use rayon::prelude::*;
 
-use super::generation::{Member, MemberWeak};
+use super::generation::Container;
 use super::genome::{Genome};
 
 
 
 
 /// Species member tuple struct to keep track of members and their fitness scores
-/// for each species, this decision was made to favor runtime over memory 
-#[derive(Debug, Clone)]
-pub struct NicheMember<T>(pub f32, pub MemberWeak<T>);
+/// for each species, the member is the index of the member in its generation.
+/// This decision was made to favor runtime over memory 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NicheMember(pub f32, pub usize);
 
 
 /// A species is meant to keep track of fitness scores of each of it's members,
 /// and a mascot. The mascot is the representation of the species by a Type 
 /// member in the population. It also holds the number of age it's been
-/// alive
+/// alive, and if the population has adaptive species, its own copy of the
+/// environment which its children are bred with. With behavioural speciation
+/// the mascot's signature is kept alongside it
 #[derive(Debug, Clone)]
 pub struct Niche<T, E> {
-    pub mascot: Member<T>,
-    pub members: Vec<NicheMember<T>>,
+    pub mascot: T,
+    pub members: Vec<NicheMember>,
     pub age: i32,
     pub total_adjusted_fitness: Option<f32>,
     pub niche_id: Uuid,
+    pub environment: Option<Arc<RwLock<E>>>,
+    pub signature: Option<Vec<f32>>,
     phantom: PhantomData<E>
 }
 
@@ -44,14 +48,16 @@ impl<T, E> Niche<T, E>
         E: Send + Sync
 {
 
-    // Create a new species with a mascot (weak member pointer)
-    pub fn new(mascot: &Member<T>, mascot_fitness: f32) -> Self {
+    // Create a new species with a mascot (a copy of the member at index in the generation)
+    pub fn new(mascot: &T, mascot_fitness: f32, index: usize) -> Self {
         Niche {
-            mascot: Arc::clone(mascot),
-            members: vec![NicheMember(mascot_fitness, Arc::downgrade(mascot))],
+            mascot: mascot.clone(),
+            members: vec![NicheMember(mascot_fitness, index)],
             age: 0,
             total_adjusted_fitness: None,
             niche_id: Uuid::new_v4(),
+            environment: None,
+            signature: None,
             phantom: PhantomData
         }
     }
@@ -59,10 +65,11 @@ impl<T, E> Niche<T, E>
 
 
     /// Get the top performing member from the species by their 
-    /// associated fitness score. If None is returned meaning there is 
-    /// no members in the species, panic!
-    pub fn fittest(&self) -> (f32, Member<T>) {
-        let mut top: Option<&NicheMember<T>> = None;
+    /// associated fitness score. The index of the member in the generation
+    /// is returned so its lineage can be followed. If None is returned meaning
+    /// there is no members in the species, panic!
+    pub fn fittest(&self) -> (f32, usize) {
+        let mut top: Option<&NicheMember> = None;
         for i in self.members.iter() {
             if top.is_none() || i.0 > top.unwrap().0 {
                 top = Some(i);
@@ -70,24 +77,25 @@ impl<T, E> Niche<T, E>
         }
 
         match top {
-            Some(t) => (t.0, Arc::new(RwLock::new((*t.1.upgrade().unwrap()).read().unwrap().clone()))),
+            Some(t) => (t.0, t.1),
             None => panic!("Failed to get top species member.")
         }
     }
 
 
 
-    /// Reset the species by getting a new random mascot and incrementing the
-    /// age by one, then setting the total adjusted species back to None,
-    /// and clearing the members vec. Basically starting from scratch again but 
-    /// need to increment a few small things to keep track of the species
-    pub fn reset(&mut self) {
+    /// Reset the species by getting a new random mascot from the generation the species'
+    /// members are in and incrementing the age by one, then setting the total adjusted
+    /// species back to None, and clearing the members vec. Basically starting from scratch
+    /// again but need to increment a few small things to keep track of the species
+    pub fn reset(&mut self, generation: &[Container<T, E>]) {
         let new_mascot = self.members.choose(&mut rand::thread_rng());
         match new_mascot {
             Some(member) => {
                 self.age += 1;
                 self.total_adjusted_fitness = None;
-                self.mascot = Arc::new(RwLock::new((*member.1.upgrade().unwrap()).read().unwrap().clone()));
+                self.mascot = generation[member.1].member.clone();
+                self.signature = generation[member.1].signature.clone();
                 self.members = Vec::new();
             }, 
             None => panic!("Failed to get new mascot")
@@ -103,10 +111,10 @@ impl<T, E> Niche<T, E>
         let length = self.members.len() as f32;
         self.total_adjusted_fitness = Some(
             self.members
-                .par_iter_mut()
+                .iter_mut()
                 .map(|x| {
                     if x.0 != 0.0 {
-                        x.0 = x.0 / length;
+                        x.0 /= length;
                     }
                     x.0
                 })
@@ -0,0 +1,151 @@
+//! Operators split the job of Genome::crossover into pieces which can be mixed,
+//! matched, and reused. A pipeline is an ordered list of recombination and mutation
+//! operators, each with their own probability of being applied to a child, so a child
+//! can be crossed over and then mutated in the same generation.
+
+extern crate rand;
+
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use rand::Rng;
+use super::{
+    genome::Genome,
+    environment::Envionment
+};
+
+
+
+/// A recombination function takes the child so far and the second parent and returns a new child
+pub type RecombineFn<T, E> = Arc<dyn Fn(&T, &T, Arc<RwLock<E>>) -> Option<T> + Send + Sync>;
+/// A mutation function takes the child so far and returns a mutated child
+pub type MutateFn<T, E> = Arc<dyn Fn(&T, Arc<RwLock<E>>) -> Option<T> + Send + Sync>;
+
+
+
+/// A single step in the pipeline, the f32 is the probability the step is applied to a child
+pub enum Operator<T, E> {
+    Recombine(String, f32, RecombineFn<T, E>),
+    Mutate(String, f32, MutateFn<T, E>)
+}
+
+
+
+/// An ordered list of operators to run over every child in the population
+pub struct Pipeline<T, E> {
+    pub operators: Vec<Operator<T, E>>
+}
+
+
+
+impl<T, E> Operator<T, E> {
+
+    /// the name of the operator
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Recombine(name, _, _) => name,
+            Self::Mutate(name, _, _) => name
+        }
+    }
+
+    /// the probability of the operator being applied
+    pub fn rate(&self) -> f32 {
+        match self {
+            Self::Recombine(_, rate, _) => *rate,
+            Self::Mutate(_, rate, _) => *rate
+        }
+    }
+}
+
+
+
+impl<T, E> Pipeline<T, E>
+    where
+        T: Send + Sync,
+        E: Envionment + Send + Sync
+{
+
+    /// an empty pipeline, with nothing in it children are copies of their most fit parent
+    pub fn new() -> Self {
+        Pipeline { operators: Vec::new() }
+    }
+
+    /// The pipeline built from the genome's own hooks, recombine the parents with probability
+    /// recombine_rate then mutate the result with probability mutate_rate
+    pub fn genome(recombine_rate: f32, mutate_rate: f32) -> Self
+        where T: Genome<T, E> + 'static
+    {
+        Pipeline::new()
+            .recombine("recombine", recombine_rate, |one: &T, two: &T, env| T::recombine(one, two, env))
+            .mutate("mutate", mutate_rate, |member: &T, env| T::mutate(member, env))
+    }
+
+    /// add a recombination operator onto the end of the pipeline
+    pub fn recombine<F>(mut self, name: &str, rate: f32, op: F) -> Self
+        where F: Fn(&T, &T, Arc<RwLock<E>>) -> Option<T> + Send + Sync + 'static
+    {
+        self.operators.push(Operator::Recombine(String::from(name), rate, Arc::new(op)));
+        self
+    }
+
+    /// add a mutation operator onto the end of the pipeline
+    pub fn mutate<F>(mut self, name: &str, rate: f32, op: F) -> Self
+        where F: Fn(&T, Arc<RwLock<E>>) -> Option<T> + Send + Sync + 'static
+    {
+        self.operators.push(Operator::Mutate(String::from(name), rate, Arc::new(op)));
+        self
+    }
+
+    /// Run the two parents through the pipeline to create a child. One is expected to be the
+    /// more fit parent, the child starts as a copy of it and each operator is applied in order with
+    /// its own probability. Recombination operators cross the child so far with the second parent.
+    #[inline]
+    pub fn apply(&self, one: &T, two: &T, env: Arc<RwLock<E>>) -> Option<T>
+        where T: Clone
+    {
+        self.apply_traced(one, two, env).map(|(child, _)| child)
+    }
+
+    /// the same as apply, but also return the names of the operators which were applied to the child
+    #[inline]
+    pub fn apply_traced(&self, one: &T, two: &T, env: Arc<RwLock<E>>) -> Option<(T, Vec<String>)>
+        where T: Clone
+    {
+        let mut r = rand::thread_rng();
+        let mut child = one.clone();
+        let mut applied = Vec::new();
+        for operator in self.operators.iter() {
+            if r.gen::<f32>() >= operator.rate() {
+                continue;
+            }
+            child = match operator {
+                Operator::Recombine(_, _, op) => op(&child, two, Arc::clone(&env))?,
+                Operator::Mutate(_, _, op) => op(&child, Arc::clone(&env))?
+            };
+            applied.push(String::from(operator.name()));
+        }
+        Some((child, applied))
+    }
+}
+
+
+
+impl<T, E> Default for Pipeline<T, E>
+    where
+        T: Send + Sync,
+        E: Envionment + Send + Sync
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+
+/// the operators themselves are closures, so just show their names and rates
+impl<T, E> fmt::Debug for Pipeline<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.operators.iter().map(|x| (x.name(), x.rate())))
+            .finish()
+    }
+}
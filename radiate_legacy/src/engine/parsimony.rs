@@ -0,0 +1,126 @@
+//! Parsimony pressure. Structures which can grow - dense pools, trees - tend to bloat because
+//! nothing stops a bigger member from being as fit as a smaller one. Before selection the fitness
+//! score of every member is adjusted by its complexity (Genome::complexity) so the smaller of two
+//! similar members is preferred. Like constraint handling only the fitness score is changed, so it
+//! works with any survival and parental criteria, and the problem's fitness is left in the objective.
+
+extern crate rand;
+
+use rand::Rng;
+use rand::seq::SliceRandom;
+use super::{
+    genome::Genome,
+    generation::Container
+};
+
+
+
+/// How the complexity of the members is pushed down
+///
+/// Lexicographic - only break ties, of two members with the same fitness the smaller one scores higher.
+///                 Members with different fitness keep their order
+/// Linear - subtract the coefficient times the complexity from the fitness
+/// DoubleTournament - hold a number of double tournaments equal to the size of the population, the winners of two
+///                    fitness tournaments of the given size compete on complexity and the smaller one wins with the given
+///                    probability. Members are ranked by the number of tournaments they won, then by their fitness,
+///                    and scored by rank
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Parsimony {
+    Lexicographic,
+    Linear(f32),
+    DoubleTournament { size: usize, probability: f32 }
+}
+
+
+
+impl Parsimony {
+
+    /// double tournament with common settings - fitness tournaments of 7 and the smaller member winning 70% of the time
+    pub fn double_tournament() -> Self {
+        Parsimony::DoubleTournament { size: 7, probability: 0.7 }
+    }
+
+    /// Adjust the fitness score of every member by its complexity. The fitness score the member
+    /// comes in with is the one adjusted, so this goes on top of any constraint handling
+    pub fn apply<T, E>(&self, members: &mut [Container<T, E>])
+        where
+            T: Genome<T, E> + Send + Sync,
+            E: Send + Sync
+    {
+        if members.is_empty() {
+            return;
+        }
+        let complexity = members.iter().map(|x| T::complexity(&x.member)).collect::<Vec<_>>();
+        match self {
+            Parsimony::Lexicographic => Parsimony::lexicographic(members, &complexity),
+            Parsimony::Linear(coefficient) => {
+                for (cont, size) in members.iter_mut().zip(complexity.iter()) {
+                    cont.fitness_score -= coefficient * size;
+                }
+            },
+            Parsimony::DoubleTournament { size, probability } => Parsimony::double_tournament_scores(members, &complexity, *size, *probability)
+        }
+    }
+
+    /// Take off less than half of the smallest gap between two different fitness scores, scaled
+    /// by the member's share of the biggest complexity, so only ties change order
+    fn lexicographic<T, E>(members: &mut [Container<T, E>], complexity: &[f32])
+        where
+            T: Genome<T, E> + Send + Sync,
+            E: Send + Sync
+    {
+        let most = complexity.iter().cloned().fold(0.0, f32::max);
+        if most <= 0.0 {
+            return;
+        }
+        let mut scores = members.iter().map(|x| x.fitness_score).collect::<Vec<_>>();
+        scores.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let gap = scores.windows(2)
+            .map(|x| x[1] - x[0])
+            .filter(|x| *x > 0.0)
+            .fold(None, |gap: Option<f32>, curr| Some(gap.map_or(curr, |g| g.min(curr))))
+            .unwrap_or(1.0);
+        for (cont, size) in members.iter_mut().zip(complexity.iter()) {
+            cont.fitness_score -= gap / 2.0 * size / (most + 1.0);
+        }
+    }
+
+    /// count the double tournaments each member wins, then spread the scores evenly
+    /// between the highest and lowest fitness by rank
+    fn double_tournament_scores<T, E>(members: &mut [Container<T, E>], complexity: &[f32], size: usize, probability: f32)
+        where
+            T: Genome<T, E> + Send + Sync,
+            E: Send + Sync
+    {
+        let mut r = rand::thread_rng();
+        let count = members.len();
+        let indexes = (0..count).collect::<Vec<_>>();
+        let fitness_tournament = |r: &mut rand::rngs::ThreadRng| {
+            indexes.choose_multiple(r, size.max(1).min(count))
+                .cloned()
+                .max_by(|a, b| members[*a].fitness_score.partial_cmp(&members[*b].fitness_score).unwrap())
+                .unwrap()
+        };
+        let mut wins = vec![0; count];
+        for _ in 0..count {
+            let (one, two) = (fitness_tournament(&mut r), fitness_tournament(&mut r));
+            let (small, large) = if complexity[one] <= complexity[two] { (one, two) } else { (two, one) };
+            if r.gen::<f32>() < probability {
+                wins[small] += 1;
+            } else {
+                wins[large] += 1;
+            }
+        }
+        let mut ranks = indexes.clone();
+        ranks.sort_by(|a, b| {
+            wins[*b].cmp(&wins[*a])
+                .then(members[*b].fitness_score.partial_cmp(&members[*a].fitness_score).unwrap())
+        });
+        let high = members.iter().map(|x| x.fitness_score).fold(f32::MIN, f32::max);
+        let low = members.iter().map(|x| x.fitness_score).fold(f32::MAX, f32::min);
+        let step = if count > 1 && high > low { (high - low) / (count - 1) as f32 } else { 1.0 };
+        for (rank, index) in ranks.into_iter().enumerate() {
+            members[index].fitness_score = high - step * rank as f32;
+        }
+    }
+}
@@ -1,17 +1,43 @@
 
 extern crate rayon;
+extern crate rand;
+extern crate serde_json;
 
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::error::Error;
+use std::mem;
 use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use rand::Rng;
 use std::marker::Sync;
 use std::fmt::Debug;
 use std::cmp::PartialEq;
 use rayon::prelude::*;
+use serde::de::DeserializeOwned;
 use super::{
     generation::{Generation, Container},
     genome::Genome,
     problem::Problem,
     environment::Envionment,
     genocide::Genocide,
+    operator::Pipeline,
+    genealogy::{Lineage, Genealogy, MemberId},
+    alps::Alps,
+    stats::GenerationStats,
+    halloffame::HallOfFame,
+    constraint::{Constraint, Constraints},
+    parsimony::Parsimony,
+    surrogate::Surrogate,
+    distance::{DistanceCache, Sampling},
+    report::{RunReport, StopReason, SpeciesSummary},
+    logger::{RunLogger, LogEntry, ChampionSummary},
+    sizing::{Sizing, Restart},
+    probes::Probes,
+    interactive::{Candidate, FillIn},
+    diagnostics,
+    random,
     survival::{SurvivalCriteria, ParentalCriteria}
 };
 
@@ -62,7 +88,33 @@ pub struct Population<T, E, P>
     solve: Arc<RwLock<P>>,
     environment: Arc<RwLock<E>>,
     survivor_criteria: SurvivalCriteria,
-    parental_criteria: ParentalCriteria
+    parental_criteria: ParentalCriteria,
+    operators: Option<Arc<Pipeline<T, E>>>,
+    genealogy: Option<Genealogy>,
+    alps: Option<Alps>,
+    layers: Vec<Generation<T, E>>,
+    seed: Option<u64>,
+    cancel: Arc<AtomicBool>,
+    stats: Option<GenerationStats>,
+    hall_of_fame: HallOfFame<T>,
+    recheck: usize,
+    constraints: Option<Constraints>,
+    parsimony: Option<Parsimony>,
+    surrogate: Option<Surrogate<T>>,
+    spawn_environment: Option<fn(&E) -> E>,
+    distance_sampling: Option<Sampling>,
+    history: Vec<GenerationStats>,
+    species_summary: Vec<SpeciesSummary>,
+    evaluations: usize,
+    logger: Option<RunLogger>,
+    sizing: Sizing,
+    restart: Option<Restart>,
+    restarts: usize,
+    run_best: Option<f32>,
+    stagnant: usize,
+    probes: Option<Arc<Probes<T>>>,
+    fill_in: FillIn,
+    track_diversity: bool
 }
 
 
@@ -101,7 +153,56 @@ impl<T, E, P> Population<T, E, P>
             // determine which genomes will live on and pass down to the next generation
             survivor_criteria: SurvivalCriteria::Fittest,
             // determine how to pick parents to reproduce
-            parental_criteria: ParentalCriteria::BiasedRandom
+            parental_criteria: ParentalCriteria::BiasedRandom,
+            // optional pipeline of recombination and mutation operators, if none is given
+            // the genome's crossover is used with the config's crossover rate
+            operators: None,
+            // optionally record the lineage of every member ever created into a genealogy
+            genealogy: None,
+            // optionally split the population into age layers, if set the current generation
+            // becomes the bottom layer on the first generation of training
+            alps: None,
+            layers: Vec::new(),
+            // seed for the random number generator handed to the problem with each member, random if not set
+            seed: None,
+            // shared flag to cancel the run from another thread
+            cancel: Arc::new(AtomicBool::new(false)),
+            // the stats of the last generation trained
+            stats: None,
+            // keep copies of the best members seen through the whole run, empty unless given a size
+            hall_of_fame: HallOfFame::new(0),
+            // the number of top members solved again on the full problem before the best is reported, 0 turns it off
+            recheck: 0,
+            // how members which violate the problem's constraints are scored for selection, if at all
+            constraints: None,
+            // how the complexity of members counts against them in selection, if at all
+            parsimony: None,
+            // a cheap model of the problem which screens members before they are solved
+            surrogate: None,
+            // how a species gets its own copy of the environment if species adapt their own settings
+            spawn_environment: None,
+            // which distances between members are cached each generation, if any
+            distance_sampling: None,
+            // the stats of every generation trained, the species of the last one, and the number of members solved
+            history: Vec::new(),
+            species_summary: Vec::new(),
+            evaluations: 0,
+            // optionally write a structured log entry for every generation
+            logger: None,
+            // how the size of the population changes between generations, fixed unless set
+            sizing: Sizing::Fixed,
+            // optionally start the population over when it stagnates, counting how many times it has
+            restart: None,
+            restarts: 0,
+            // the best score since the run started or last restarted and how many generations it has gone without improving
+            run_best: None,
+            stagnant: 0,
+            // the probe inputs members are run on if they are speciated by behaviour
+            probes: None,
+            // how members left unrated get their fitness when the population is rated by hand
+            fill_in: FillIn::Mean,
+            // measure the mean distance between every pair of members each generation for the stats
+            track_diversity: false
         }
     }
 
@@ -120,6 +221,28 @@ impl<T, E, P> Population<T, E, P>
         self.curr_gen.member(idx)
     }
 
+    /// Get the genealogy of the run, this is only recorded if it was turned on with track_lineage
+    pub fn genealogy(&self) -> Option<&Genealogy> {
+        self.genealogy.as_ref()
+    }
+
+    /// Get the flag used to cancel the run. Setting it to true from any thread stops run after the
+    /// current generation and is passed to the problem with every member so it can stop early as well
+    pub fn cancel_handle(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.cancel)
+    }
+
+    /// the seed members are solved with this generation, the population's seed if it has one otherwise a random one
+    fn generation_seed(&self) -> u64 {
+        self.seed.unwrap_or_else(|| rand::thread_rng().gen())
+    }
+
+    /// Get the age layers of the population, these are only used if the population is run with alps.
+    /// Index 0 is the bottom (youngest) layer
+    pub fn layers(&self) -> &[Generation<T, E>] {
+        &self.layers
+    }
+
     /// Each generation will be trained by a call to this function 
     /// resulting optimization of the current generation, up to a 
     /// crossover into the next generation which will be set to the 
@@ -130,11 +253,172 @@ impl<T, E, P> Population<T, E, P>
             T: Genome<T, E> + Clone + Send + Sync + Debug + PartialEq,
             P: Send + Sync
     {
+        if self.alps.is_some() {
+            return self.train_layers();
+        }
         // optimize the population 
-        self.curr_gen.optimize(self.solve.clone());
+        let seed = self.generation_seed();
+        self.curr_gen.probes = self.probes.clone();
+        if let Some(surrogate) = self.surrogate.as_mut() { surrogate.screen(&mut self.curr_gen.members); }
+        self.curr_gen.optimize_seeded(self.solve.clone(), seed, &self.cancel);
+        self.evaluations += self.curr_gen.members.iter().filter(|x| !x.predicted).count();
+        if let Some(surrogate) = self.surrogate.as_mut() { surrogate.learn(&self.curr_gen.members); }
         self.end_generation()
     }
 
+    /// The members of the current generation waiting to be rated. After the population is given
+    /// its ratings the next generation is bred, and these are the members of that one
+    pub fn candidates(&self) -> Vec<Candidate<'_, T>> {
+        self.curr_gen.members
+            .iter()
+            .enumerate()
+            .map(|(index, cont)| Candidate {
+                index,
+                id: cont.lineage.id,
+                species: cont.lineage.species,
+                member: &cont.member
+            })
+            .collect()
+    }
+
+    /// Train the current generation on ratings given from outside instead of solving the problem, for when
+    /// the fitness comes from people. Ratings are given by the id of the candidate and don't have to cover
+    /// every candidate, the rest are filled in the way the population's fill in says. The generation is
+    /// then finished like any other and the next one is bred and waits in the candidates to be rated.
+    /// Returns the top rated member and its rating. Age layers can't be rated, so this errors with alps
+    pub fn rate(&mut self, ratings: &[(MemberId, f32)]) -> Result<(f32, T), &'static str>
+        where
+            T: Genome<T, E> + Clone + Send + Sync + Debug + PartialEq,
+            P: Send + Sync
+    {
+        if self.alps.is_some() {
+            return Err("Age layers can't be rated");
+        }
+        self.fill_in.apply(&mut self.curr_gen.members, ratings, self.surrogate.as_mut())?;
+        self.evaluations += self.curr_gen.members.iter().filter(|x| !x.predicted).count();
+        self.curr_gen.probes = self.probes.clone();
+        if let Some(probes) = self.probes.as_ref() {
+            for cont in self.curr_gen.members.iter_mut() {
+                cont.signature = Some(probes.signature(&mut cont.member));
+            }
+        }
+        self.end_generation().ok_or("Error Training")
+    }
+
+    /// Train one generation of age layers. Every layer is optimized, members who have grown
+    /// too old for their layer move up a layer, then each layer is speciated and creates its
+    /// next generation from parents in itself and the layer below. Every age gap generations
+    /// the bottom layer is replaced with brand new members from the genome's base function.
+    fn train_layers(&mut self) -> Option<(f32, T)>
+        where 
+            T: Genome<T, E> + Clone + Send + Sync + Debug + PartialEq,
+            P: Send + Sync
+    {
+        let alps = self.alps.clone()?;
+        // on the first generation the current generation becomes the bottom layer and the rest start empty
+        if self.layers.is_empty() {
+            self.layers.push(mem::replace(&mut self.curr_gen, Generation::new()));
+            for _ in 1..alps.layers {
+                let mut layer = Generation::new();
+                layer.index = self.layers[0].index;
+                self.layers.push(layer);
+            }
+        }
+        let seed = self.generation_seed();
+        for (i, layer) in self.layers.iter_mut().enumerate() {
+            layer.probes = self.probes.clone();
+            if let Some(surrogate) = self.surrogate.as_mut() { surrogate.screen(&mut layer.members); }
+            layer.optimize_seeded(self.solve.clone(), random::mix(seed, i as u64, 0), &self.cancel);
+            self.evaluations += layer.members.iter().filter(|x| !x.predicted).count();
+            if let Some(surrogate) = self.surrogate.as_mut() { surrogate.learn(&layer.members); }
+        }
+        for layer in self.layers.iter_mut() {
+            if self.spawn_environment.is_some() { layer.adapt_species(); }
+            if let Some(constraints) = self.constraints.as_ref() { constraints.score(&mut layer.members); }
+        }
+        // the penalty adapts to the generation as a whole, not to each layer
+        if let Some(constraints) = self.constraints.as_mut() { constraints.adapt(self.layers.iter().flat_map(|x| x.members.iter())); }
+        // move the members who are too old for their layer up, starting from the top so nobody moves twice
+        for i in (0..self.layers.len() - 1).rev() {
+            let max_age = alps.max_age(i).unwrap_or(usize::MAX);
+            let (old, young) = self.layers[i].members
+                .drain(..)
+                .partition::<Vec<_>, _>(|x| x.age > max_age);
+            self.layers[i].members = young;
+            self.layers[i + 1].members.extend(old);
+        }
+        let top_member = self.layers
+            .iter()
+            .filter_map(|x| self.best_of(x))
+            .fold(None, |top: Option<(f32, Arc<T>)>, curr| match top {
+                Some(t) if t.0 >= curr.0 => Some(t),
+                _ => Some(curr)
+            })?;
+        // each layer holds its own species
+        for layer in self.layers.iter_mut() {
+            if let Some(parsimony) = &self.parsimony { parsimony.apply(&mut layer.members); }
+            if let Some(sampling) = &self.distance_sampling { layer.cache_distances(sampling, Arc::clone(&self.environment)); }
+            layer.speciate(self.config.distance, Arc::clone(&self.environment));
+            if let Some(spawn) = self.spawn_environment { layer.spawn_environments(&self.environment, spawn); }
+        }
+        self.species_summary = self.layers.iter().flat_map(|x| SpeciesSummary::from_generation(x)).collect();
+        self.manage_stagnation(top_member.0);
+        if self.debug_progress { self.show_progress(); }
+        if let Some(genealogy) = self.genealogy.as_mut() {
+            for layer in self.layers.iter() {
+                layer.record(genealogy);
+            }
+        }
+        for layer in self.layers.iter() {
+            self.hall_of_fame.update(layer);
+        }
+        self.track_improvement(top_member.0);
+        let mut stats = GenerationStats::from_generations(&self.layers);
+        if self.track_diversity {
+            let members = self.layers.iter().flat_map(|x| x.members.iter().map(|c| &c.member)).collect::<Vec<_>>();
+            stats.diversity = diagnostics::diversity(&members, &self.environment);
+        }
+        self.advance_problem(stats);
+        if self.logger.is_some() {
+            let entry = self.log_entry(&self.layers.iter().collect::<Vec<_>>(), &top_member);
+            if let Some(logger) = self.logger.as_mut() { logger.log(&entry); }
+        }
+        if self.restart_due() {
+            self.start_over();
+            return Some((top_member.0, (*top_member.1).clone()));
+        }
+        self.resize();
+        // create the next generation of each layer from the top down, a layer's species are reset
+        // when it creates its next generation so the layer below has to go after the layer above it
+        for i in (0..self.layers.len()).rev() {
+            let (below, rest) = self.layers.split_at_mut(i);
+            let below = below.last();
+            let layer = &mut rest[0];
+            layer.operators = self.operators.clone();
+            layer.survival_criteria = self.survivor_criteria.clone();
+            layer.parental_criteria = self.parental_criteria.clone();
+            // a layer with nobody in it or below it has nothing to breed from, it stays empty
+            if layer.species.is_empty() && below.map_or(true, |x| x.species.is_empty()) {
+                layer.members.clear();
+                layer.index += 1;
+                continue;
+            }
+            rest[0] = match below {
+                Some(below) => layer.create_next_generation_from(below, self.size, self.config.clone(), Arc::clone(&self.environment))?,
+                None => layer.create_next_generation(self.size, self.config.clone(), Arc::clone(&self.environment))?
+            };
+        }
+        // bring in fresh genetic material to the bottom layer
+        let bottom = &mut self.layers[0];
+        if alps.inject(bottom.index) || bottom.members.is_empty() {
+            let mut lock_set = self.environment.write().unwrap();
+            bottom.members = (0..self.size)
+                .map(|_| Container::new(T::base(&mut lock_set), Lineage::new(bottom.index, Vec::new(), Vec::new())))
+                .collect();
+        }
+        Some((top_member.0, (*top_member.1).clone()))
+    }
+
     /// Handle end of generation calculations and create a new generation.
     /// Returns the top member and their score.
     pub fn end_generation(&mut self) -> Option<(f32, T)>
@@ -142,27 +426,232 @@ impl<T, E, P> Population<T, E, P>
             T: Genome<T, E> + Clone + Send + Sync + Debug + PartialEq,
             P: Send + Sync
     {
-        let top_member = self.curr_gen.best_member()?;
+        if self.spawn_environment.is_some() { self.curr_gen.adapt_species(); }
+        if let Some(constraints) = self.constraints.as_mut() { constraints.apply(&mut self.curr_gen.members); }
+        let top_member = self.best_of(&self.curr_gen)?;
+        // the best is picked before parsimony so it is reported with its real fitness, the objective keeps it for the rest
+        if let Some(parsimony) = &self.parsimony { parsimony.apply(&mut self.curr_gen.members); }
         // adjust the distance of the population if needed
         if self.dynamic_distance { self.adjust_distance(); }
         // speciate the generation into niches then see if the population is stagnant
         // if the population is stagnant, clean the population 
+        if let Some(sampling) = &self.distance_sampling { self.curr_gen.cache_distances(sampling, Arc::clone(&self.environment)); }
         self.curr_gen.speciate(self.config.distance, Arc::clone(&self.environment));
+        if let Some(spawn) = self.spawn_environment { self.curr_gen.spawn_environments(&self.environment, spawn); }
+        self.species_summary = SpeciesSummary::from_generation(&self.curr_gen);
         self.manage_stagnation(top_member.0);
         // If debug is set to true, this is the place to show it before the new generation is 
         if self.debug_progress { self.show_progress(); }
+        // now the members have their fitness and species, record them if lineage is being tracked
+        if let Some(genealogy) = self.genealogy.as_mut() { self.curr_gen.record(genealogy); }
+        // keep the best members seen and let the problem know how the generation went
+        self.hall_of_fame.update(&self.curr_gen);
+        self.track_improvement(top_member.0);
+        let mut stats = GenerationStats::from_generation(&self.curr_gen);
+        if self.track_diversity {
+            let members = self.curr_gen.members.iter().map(|x| &x.member).collect::<Vec<_>>();
+            stats.diversity = diagnostics::diversity(&members, &self.environment);
+        }
+        self.advance_problem(stats);
+        if self.logger.is_some() {
+            let entry = self.log_entry(&[&self.curr_gen], &top_member);
+            if let Some(logger) = self.logger.as_mut() { logger.log(&entry); }
+        }
+        // start over or resize the population before the next generation is created
+        if self.restart_due() {
+            self.start_over();
+            return Some((top_member.0, (*top_member.1).clone()));
+        }
+        self.resize();
         // create a new generation and return it
+        self.curr_gen.operators = self.operators.clone();
+        self.curr_gen.survival_criteria = self.survivor_criteria.clone();
+        self.curr_gen.parental_criteria = self.parental_criteria.clone();
         self.curr_gen = self.curr_gen.create_next_generation(self.size, self.config.clone(), Arc::clone(&self.environment))?;
         // return the top member score and the member
         Some((top_member.0, (*top_member.1).clone()))
     }
 
+    /// The best member of a generation, double checked against the full problem if recheck is set.
+    /// With constraint handling the best is picked by the feasibility rules and reported with its objective,
+    /// and only feasible members are rechecked
+    fn best_of(&self, gen: &Generation<T, E>) -> Option<(f32, Arc<T>)>
+        where P: Send + Sync
+    {
+        match (self.recheck > 0, self.constraints.is_some()) {
+            (true, true) => gen.best_feasible_rechecked(self.recheck, &*self.solve.read().unwrap()),
+            (true, false) => gen.best_member_rechecked(self.recheck, &*self.solve.read().unwrap()),
+            (false, true) => gen.best_feasible(),
+            (false, false) => gen.best_member()
+        }
+    }
+
+    /// keep count of the generations since the best score of the run (since the last restart) improved
+    fn track_improvement(&mut self, top_score: f32) {
+        match self.run_best {
+            Some(best) if top_score <= best => self.stagnant += 1,
+            _ => {
+                self.run_best = Some(top_score);
+                self.stagnant = 0;
+            }
+        }
+    }
+
+    /// true if the population has a restart strategy and has gone long enough without improving
+    fn restart_due(&self) -> bool {
+        self.restart.as_ref().is_some_and(|x| x.stagnation() > 0 && self.stagnant >= x.stagnation())
+    }
+
+    /// Start the population over with brand new members, and age layers over from the bottom layer.
+    /// The generation index keeps counting, and the hall of fame, genealogy and history are kept
+    fn start_over(&mut self) {
+        if let Some(restart) = self.restart.as_ref() {
+            self.size = restart.restart_size(self.size);
+        }
+        let index = Some(&self.curr_gen)
+            .into_iter()
+            .chain(self.layers.iter())
+            .map(|x| x.index)
+            .max()
+            .unwrap_or(0) + 1;
+        self.layers.clear();
+        self.curr_gen = self.fresh_generation(index);
+        self.restarts += 1;
+        self.run_best = None;
+        self.stagnant = 0;
+        self.stagnation.current_stagnation = 0;
+    }
+
+    /// set the size of the next generation from the sizing
+    fn resize(&mut self) {
+        if let Some(stats) = self.stats.as_ref() {
+            self.size = self.sizing.next_size(self.size, stats, self.stagnant);
+        }
+    }
+
+    /// a generation of brand new members from the genome's base function, born at the given index
+    fn fresh_generation(&self, index: usize) -> Generation<T, E> {
+        let mut gen = Generation::new();
+        gen.index = index;
+        let environment = &self.environment;
+        gen.members = (0..self.size)
+            .into_par_iter()
+            .map(|_| {
+                let mut lock_set = environment.write().unwrap();
+                Container::new(T::base(&mut lock_set), Lineage::new(index, Vec::new(), Vec::new()))
+            })
+            .collect();
+        gen
+    }
+
+    /// The log entry of the generation(s) just trained, this has to be called after the stats are set
+    fn log_entry(&self, gens: &[&Generation<T, E>], top_member: &(f32, Arc<T>)) -> LogEntry
+        where T: PartialEq
+    {
+        LogEntry {
+            stats: self.stats.clone().unwrap_or_default(),
+            species: self.species_summary.clone(),
+            champion: ChampionSummary::find(gens, top_member.0, &top_member.1),
+            config: self.config.clone()
+        }
+    }
+
+    /// Hand the stats of the generation to the problem, if the problem changes itself then
+    /// everything scored against the old problem is thrown out
+    fn advance_problem(&mut self, stats: GenerationStats) {
+        let changed = self.solve.write().unwrap().advance(&stats);
+        if changed {
+            self.problem_changed();
+        }
+        self.history.push(stats.clone());
+        self.stats = Some(stats);
+    }
+
+    /// The problem is not the one the scores were taken against anymore, so the hall of fame is
+    /// re-scored, the surrogate forgets what it learned, and stagnation starts over because old
+    /// scores no longer compare
+    fn problem_changed(&mut self) {
+        self.hall_of_fame.rescore(&*self.solve.read().unwrap());
+        if let Some(surrogate) = self.surrogate.as_mut() { surrogate.clear(); }
+        self.stagnation.current_stagnation = 0;
+        self.stagnation.previous_top_score = self.hall_of_fame.best().map_or(0.0, |x| x.fitness);
+        self.run_best = None;
+        self.stagnant = 0;
+    }
+
+    /// Swap the problem during a run, for example on a schedule. Everything scored against the
+    /// old problem is thrown out the same way as when the problem advances itself
+    pub fn swap_problem(&mut self, prob: P) {
+        self.solve = Arc::new(RwLock::new(prob));
+        self.problem_changed();
+    }
+
+    /// Get the problem the population is solving
+    pub fn problem(&self) -> Arc<RwLock<P>> {
+        Arc::clone(&self.solve)
+    }
+
+    /// Get the stats of the last generation trained
+    pub fn stats(&self) -> Option<&GenerationStats> {
+        self.stats.as_ref()
+    }
+
+    /// Get the stats of every generation trained so far, oldest first
+    pub fn history(&self) -> &[GenerationStats] {
+        &self.history
+    }
+
+    /// Get the number of members the problem has solved so far
+    pub fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    /// Get the run logger if the population has one, it holds the error which stopped it if writing failed
+    pub fn logger(&self) -> Option<&RunLogger> {
+        self.logger.as_ref()
+    }
+
+    /// Get the number of times the population has started over
+    pub fn restarts(&self) -> usize {
+        self.restarts
+    }
+
+    /// Get the hall of fame, the best members seen over the whole run
+    pub fn get_hall_of_fame(&self) -> &HallOfFame<T> {
+        &self.hall_of_fame
+    }
+
+    /// the environments of the current species if they adapt their own, otherwise empty
+    pub fn species_environments(&self) -> Vec<Arc<RwLock<E>>> {
+        Some(&self.curr_gen)
+            .into_iter()
+            .chain(self.layers.iter())
+            .flat_map(|gen| gen.species.iter())
+            .filter_map(|spec| spec.environment.clone())
+            .collect()
+    }
+
+    /// the constraint handling if there is one, with the current penalty if it adapts
+    pub fn get_constraints(&self) -> Option<&Constraints> {
+        self.constraints.as_ref()
+    }
+
+    /// the surrogate model if there is one, it keeps count of how many solves it saved
+    pub fn get_surrogate(&self) -> Option<&Surrogate<T>> {
+        self.surrogate.as_ref()
+    }
+
     /// Check to see if the population is stagnant or not, if it is,
     /// then go ahead and clean the population 
     fn manage_stagnation(&mut self, curr_top_score: f32) {
         if self.stagnation.target_stagnation == self.stagnation.current_stagnation {
             for cleaner in self.stagnation.cleaners.iter() {
-                cleaner.kill(&mut self.curr_gen);
+                if self.layers.is_empty() {
+                    cleaner.kill(&mut self.curr_gen);
+                }
+                for layer in self.layers.iter_mut() {
+                    cleaner.kill(layer);
+                }
             }
             self.stagnation.current_stagnation = 0;
         } else if curr_top_score == self.stagnation.previous_top_score {
@@ -188,24 +677,34 @@ impl<T, E, P> Population<T, E, P>
     /// Run the population according to a user defined function, the inputs of which
     /// are a borrowed member which is the top member of the current generation, 
     /// the fitness of that member, and the current number of generations.
-    /// This function will continue until this function returns a true value 
-    pub fn run<F>(&mut self, runner: F) -> Result<(T, E), &'static str>
+    /// This function will continue until this function returns a true value or the run is
+    /// cancelled, then the report of the run is returned with the last top member as its champion.
+    /// If the run is cancelled before a generation is trained there is no champion and it is an error
+    pub fn run<F>(&mut self, runner: F) -> Result<RunReport<T, E>, &'static str>
         where 
             F: Fn(&T, f32, i32) -> bool + Sized,
             T: Genome<T, E> + Clone + Send + Sync + Debug + PartialEq,
             P: Send + Sync,
             E: Clone
     {
+        let start = Instant::now();
+        let first = self.history.len();
         let mut index = 0;
+        let mut champion = None;
         loop {
+            if self.cancel.load(Ordering::Relaxed) {
+                return match champion {
+                    Some((fit, top)) => Ok(self.report(top, fit, first, start, StopReason::Cancelled)),
+                    None => Err("Training Cancelled")
+                };
+            }
             match self.train() {
                 Some(result) => {
                     let (fit, top) = result;
                     if runner(&top, fit, index) {
-                        let solution = top.clone();
-                        let env = (*self.environment.read().unwrap()).clone();
-                        return Ok((solution, env));
+                        return Ok(self.report(top, fit, first, start, StopReason::Satisfied));
                     }
+                    champion = Some((fit, top));
                     index += 1;
                 },
                 None => return Err("Error Training")
@@ -213,12 +712,34 @@ impl<T, E, P> Population<T, E, P>
         }
     }
 
+    /// build the report of a run which started with the history at first
+    fn report(&self, champion: T, fitness: f32, first: usize, start: Instant, stop: StopReason) -> RunReport<T, E>
+        where E: Clone
+    {
+        RunReport {
+            champion,
+            fitness,
+            environment: (*self.environment.read().unwrap()).clone(),
+            history: self.history[first..].to_vec(),
+            evaluations: self.evaluations,
+            elapsed: start.elapsed(),
+            stop,
+            species: self.species_summary.clone()
+        }
+    }
+
     /// if debug is set to true, this is what will print out 
     /// the training to the screen during optimization.
     fn show_progress(&self) {
         println!("\n");
         for i in self.curr_gen.species.iter() {
-            i.read().unwrap().display_info();
+            i.display_info();
+        }
+        for (index, layer) in self.layers.iter().enumerate() {
+            println!("Layer: {} members( {} )", index, layer.members.len());
+            for i in layer.species.iter() {
+                i.display_info();
+            }
         }
     }
     
@@ -239,6 +760,10 @@ impl<T, E, P> Population<T, E, P>
     /// 4.) populate_clone - Take a base type T and create a population that is made up 
     ///                      completely of clones of this type - they will all be the same 
     ///                      at least for the first generation, this is useful for algorithms like NEAT
+    /// 5.) populate_variants - Take a vec of type T from earlier runs and fill the rest of the
+    ///                         population with mutated copies of them
+    /// 6.) populate_from_files/populate_from_dir - The same as populate_variants but the members are
+    ///                         loaded from json files, like the ones saved with Neat::save
     
     /// give the populate a direct generation object 
     pub fn populate_gen(mut self, gen: Generation<T, E>) -> Self {
@@ -250,22 +775,7 @@ impl<T, E, P> Population<T, E, P>
     pub fn populate_base(mut self) -> Self 
         where P: Send + Sync
     {
-        self.curr_gen = Generation {
-            members: (0..self.size)
-                .into_par_iter()
-                .map(|_| {
-                    let mut lock_set = self.environment.write().unwrap();
-                    Container {
-                        member: Arc::new(RwLock::new(T::base(&mut lock_set))),
-                        fitness_score: 0.0,
-                        species: None
-                    }    
-                })
-                .collect(),
-            species: Vec::new(),
-            survival_criteria: SurvivalCriteria::Fittest,
-            parental_criteria: ParentalCriteria::BiasedRandom
-        };
+        self.curr_gen = self.fresh_generation(0);
         self
     }
     
@@ -273,17 +783,17 @@ impl<T, E, P> Population<T, E, P>
     pub fn populate_vec(mut self, vals: Vec<T>) -> Self {
         self.curr_gen = Generation {
             members: vals.into_iter()
-                .map(|x| {
-                    Container {
-                        member: Arc::new(RwLock::new(x)),
-                        fitness_score: 0.0,
-                        species: None
-                    }
-                })
+                .map(|x| Container::new(x, Lineage::new(0, Vec::new(), Vec::new())))
                 .collect(),
             species: Vec::new(),
             survival_criteria: SurvivalCriteria::Fittest,
-            parental_criteria: ParentalCriteria::BiasedRandom
+            parental_criteria: ParentalCriteria::BiasedRandom,
+            operators: None,
+            distances: DistanceCache::new(),
+            probes: None,
+            index: 0,
+            repairs: 0,
+            discards: 0
         };
         self
     }
@@ -295,21 +805,78 @@ impl<T, E, P> Population<T, E, P>
         self.curr_gen = Generation {
             members: (0..self.size as usize)
                 .into_iter()
-                .map(|_| {
-                    Container {
-                        member: Arc::new(RwLock::new(original.clone())),
-                        fitness_score: 0.0,
-                        species: None
-                    }
-                })
+                .map(|_| Container::new(original.clone(), Lineage::new(0, Vec::new(), Vec::new())))
                 .collect(),
             species: Vec::new(),
             survival_criteria: SurvivalCriteria::Fittest,
-            parental_criteria: ParentalCriteria::BiasedRandom
+            parental_criteria: ParentalCriteria::BiasedRandom,
+            operators: None,
+            distances: DistanceCache::new(),
+            probes: None,
+            index: 0,
+            repairs: 0,
+            discards: 0
         };
         self
     }
 
+    /// Warm start the population from members found before. Every seed is kept as is (up to the
+    /// size of the population) and the rest of the population is filled with variants of them, each
+    /// seed in turn is mutated through the genome's crossover with the crossover rate set to 0. A variant
+    /// which isn't valid is repaired, or if it can't be it is left as a copy of its seed
+    pub fn populate_variants(mut self, seeds: Vec<T>) -> Self {
+        let mut members = seeds.into_iter()
+            .take(self.size.max(0) as usize)
+            .map(|x| Container::new(x, Lineage::new(0, Vec::new(), vec![String::from("seed")])))
+            .collect::<Vec<_>>();
+        // without any seeds there is nothing to make variants of
+        let seeds = members.len();
+        let size = if seeds == 0 { 0 } else { self.size.max(0) as usize };
+        let environment = &self.environment;
+        let variants = (seeds..size)
+            .into_par_iter()
+            .map(|i| {
+                let seed = &members[i % seeds];
+                let variant = <T as Genome<T, E>>::crossover(&seed.member, &seed.member, Arc::clone(environment), 0.0)
+                    .map(|x| Generation::check(x, environment).unwrap_or_else(|fixed| fixed.unwrap_or_else(|| seed.member.clone())))
+                    .unwrap_or_else(|| seed.member.clone());
+                Container::new(variant, Lineage::new(0, vec![seed.lineage.id], vec![String::from("mutate")]))
+            })
+            .collect::<Vec<_>>();
+        members.extend(variants);
+        let mut gen = Generation::new();
+        gen.members = members;
+        self.curr_gen = gen;
+        self
+    }
+
+    /// Warm start the population from members saved as json files, see populate_variants.
+    /// It is an error if a file can't be read or none of the files are given
+    pub fn populate_from_files(self, file_paths: &[&str]) -> Result<Self, Box<dyn Error>>
+        where T: DeserializeOwned
+    {
+        if file_paths.is_empty() {
+            return Err("No files to populate from".into());
+        }
+        let seeds = file_paths.iter()
+            .map(|path| Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?))
+            .collect::<Result<Vec<T>, Box<dyn Error>>>()?;
+        Ok(self.populate_variants(seeds))
+    }
+
+    /// Warm start the population from every .json file in a directory (in order of their names), see populate_variants
+    pub fn populate_from_dir(self, dir: &str) -> Result<Self, Box<dyn Error>>
+        where T: DeserializeOwned
+    {
+        let mut paths = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|x| x.path()))
+            .filter(|path| path.extension().is_some_and(|x| x == "json"))
+            .filter_map(|path| path.to_str().map(String::from))
+            .collect::<Vec<_>>();
+        paths.sort();
+        self.populate_from_files(&paths.iter().map(|x| x.as_str()).collect::<Vec<_>>())
+    }
+
     /// Give solver settings to the population to evolve the structure defined
     pub fn constrain(mut self, environment: E) -> Self {
         self.environment = Arc::new(RwLock::new(environment));
@@ -365,6 +932,34 @@ impl<T, E, P> Population<T, E, P>
         self
     }
 
+    /// Change the size of the population from generation to generation, the size set
+    /// with size is the starting size
+    pub fn sizing(mut self, sizing: Sizing) -> Self {
+        self.sizing = sizing;
+        self
+    }
+
+    /// Start the population over with new members when it stagnates, the
+    /// hall of fame is kept through restarts so give it a size to keep the best found
+    pub fn restart(mut self, restart: Restart) -> Self {
+        self.restart = Some(restart);
+        self
+    }
+
+    /// Speciate members by how they behave on the probes instead of, or blended with, the genome's
+    /// distance. Every member is run on the probes while it is evaluated for its signature
+    pub fn behavioural_speciation(mut self, probes: Probes<T>) -> Self {
+        self.probes = Some(Arc::new(probes));
+        self
+    }
+
+    /// Write a JSON line for every generation to the logger, this is separate from debug so
+    /// the run can be logged for later without printing anything to the screen
+    pub fn log_to(mut self, logger: RunLogger) -> Self {
+        self.logger = Some(logger);
+        self
+    }
+
     /// give the population a survival criteria, if none is supplied then it
     /// defaults to the fittest genome from each species
     pub fn survivor_criteria(mut self, survive: SurvivalCriteria) -> Self {
@@ -378,6 +973,110 @@ impl<T, E, P> Population<T, E, P>
         self.parental_criteria =parents;
         self
     }
+
+    /// give the population a pipeline of operators to create children with, if none 
+    /// is supplied then children are created by the genome's crossover function
+    pub fn operators(mut self, pipeline: Pipeline<T, E>) -> Self {
+        self.operators = Some(Arc::new(pipeline));
+        self
+    }
+
+    /// Run the population as age layers (ALPS). The population size becomes the size of each layer
+    /// and the populated generation becomes the bottom layer. Members can only compete with members
+    /// in their own layer and the layer below, and the bottom layer is regularly refilled with new
+    /// members from the genome's base function so the population keeps exploring
+    pub fn alps(mut self, alps: Alps) -> Self {
+        self.alps = Some(alps);
+        self
+    }
+
+    /// Seed the random number generator given to the problem with each member, with the same
+    /// seed each member gets the same random numbers for its generation and position every run
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Keep copies of the best size members seen over the whole run. When the problem changes
+    /// they are re-scored so it always holds the best members for the current problem
+    pub fn hall_of_fame(mut self, size: usize) -> Self {
+        self.hall_of_fame = HallOfFame::new(size);
+        self
+    }
+
+    /// Before reporting the best member of a generation, solve the top k members again with
+    /// Problem::solve_full and report the best of those by their full score. This is meant for
+    /// problems which only solve on a mini-batch of their data each generation
+    pub fn recheck(mut self, k: usize) -> Self {
+        self.recheck = k;
+        self
+    }
+
+    /// Handle the constraints reported by Problem::constraint_violation. Before selection every member's
+    /// fitness score is replaced by its score under the constraint handling, so the survival and parental
+    /// criteria pick members by it. The best member is reported by the feasibility rules with its real fitness,
+    /// only feasible members make it into the hall of fame, and the stats only count feasible members if there are any
+    pub fn constraints(mut self, handling: Constraint) -> Self {
+        self.constraints = Some(Constraints::new(handling));
+        self
+    }
+
+    /// How members left unrated are given their fitness when the population is rated with rate, the mean rating by default
+    pub fn fill_in(mut self, fill_in: FillIn) -> Self {
+        self.fill_in = fill_in;
+        self
+    }
+
+    /// Push down the size of the members (Genome::complexity) to keep them from bloating. Before selection
+    /// and after any constraint handling every member's fitness score is adjusted by its complexity. The best
+    /// member is picked before that, and the hall of fame, stats and genealogy read the problem's fitness from
+    /// the objective, so everything is still reported with its real fitness
+    pub fn parsimony(mut self, parsimony: Parsimony) -> Self {
+        self.parsimony = Some(parsimony);
+        self
+    }
+
+    /// Give every species its own copy of the environment to breed with. Each generation a species'
+    /// environment adapts by how many of the children bred with it beat their fittest parent, see
+    /// Envionment::adapt, so some species can explore while others fine tune. New species start
+    /// from the environment their mascot was bred with
+    pub fn adaptive_species(mut self, adapt: bool) -> Self
+        where E: Clone
+    {
+        self.spawn_environment = if adapt { Some(E::clone) } else { None };
+        self
+    }
+
+    /// Cache the distances between members each generation. Speciation, diversity statistics and ParentalCriteria::MostDifferent
+    /// all use the cached distances. Sampling::Exact caches every distance between members the first time it is asked for,
+    /// Sampling::Sampled compares each member to a few others up front in parallel before the generation is speciated
+    pub fn distance_cache(mut self, sampling: Sampling) -> Self {
+        self.distance_sampling = Some(sampling);
+        self
+    }
+
+    /// Measure the diversity of every generation as the mean Genome::distance between every pair of members
+    /// and report it in the stats, replacing the mean of the distance cache. This is exact so it costs
+    /// n * (n - 1) / 2 distances a generation, see diagnostics::diversity
+    pub fn track_diversity(mut self, track: bool) -> Self {
+        self.track_diversity = track;
+        self
+    }
+
+    /// Screen every generation with a surrogate model of the problem before it is solved. Only the
+    /// most promising members by the model's prediction are solved, the rest keep the predicted fitness
+    pub fn surrogate(mut self, surrogate: Surrogate<T>) -> Self {
+        self.surrogate = Some(surrogate);
+        self
+    }
+
+    /// Record the lineage of every member into a genealogy as the population evolves - each member
+    /// always carries its own lineage, this keeps the full ancestry graph of the run so it can be
+    /// exported once training is done. The graph grows every generation so it is off by default
+    pub fn track_lineage(mut self, track: bool) -> Self {
+        self.genealogy = if track { Some(Genealogy::new()) } else { None };
+        self
+    }
 }
 
 
@@ -0,0 +1,97 @@
+//! Behavioural speciation. The distance a genome defines is genotypic - for neat it is the
+//! innovation ids the networks share, for an evtree it is a sine of its weights - and two members
+//! far apart by it can still behave the same. Probes are a set of inputs given by the user which every
+//! member is run on while it is being evaluated, its outputs on all of them are its signature. The
+//! distance between two signatures is how differently the members behave, and can be used for
+//! speciation in place of the genome's distance or blended with it.
+
+use std::fmt;
+use std::sync::Arc;
+
+
+
+/// run a member on a single probe input and return its output
+pub type ProbeFn<T> = Arc<dyn Fn(&mut T, &[f32]) -> Vec<f32> + Send + Sync>;
+
+
+
+/// The probe inputs and how to run a member on them
+///
+/// inputs: the probe inputs, the same ones for every member
+/// blend: how much of the distance between members is behavioural, 1.0 only uses the signatures
+///        and 0.0 only uses the genome's distance
+pub struct Probes<T> {
+    pub inputs: Vec<Vec<f32>>,
+    pub blend: f32,
+    output: ProbeFn<T>
+}
+
+
+
+impl<T> Probes<T> {
+
+    /// Create probes from the inputs and the function which runs a member on one input,
+    /// for neat this is something like |net, input| net.forward(&input.to_vec()).unwrap().
+    /// By default only the behaviour is used
+    pub fn new<F>(inputs: Vec<Vec<f32>>, output: F) -> Self
+        where F: Fn(&mut T, &[f32]) -> Vec<f32> + Send + Sync + 'static
+    {
+        Probes {
+            inputs,
+            blend: 1.0,
+            output: Arc::new(output)
+        }
+    }
+
+    /// how much of the distance between members is behavioural, between 0 and 1
+    pub fn blend(mut self, blend: f32) -> Self {
+        self.blend = blend.clamp(0.0, 1.0);
+        self
+    }
+
+    /// the member's signature, its outputs on every probe one after the other
+    pub fn signature(&self, member: &mut T) -> Vec<f32> {
+        self.inputs
+            .iter()
+            .flat_map(|input| (self.output)(member, input))
+            .collect()
+    }
+
+    /// The distance between two signatures, the root mean square difference of their outputs.
+    /// If one signature is longer than the other the extra outputs are left out
+    pub fn behaviour_distance(one: &[f32], two: &[f32]) -> f32 {
+        let length = one.len().min(two.len());
+        if length == 0 {
+            return 0.0;
+        }
+        let total = one.iter()
+            .zip(two.iter())
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f32>();
+        (total / length as f32).sqrt()
+    }
+
+    /// The distance between two members given their signatures and a function for their genome's distance,
+    /// which is only called if it is part of the blend. Without both signatures it is the genome's distance
+    pub fn distance<F>(&self, one: Option<&[f32]>, two: Option<&[f32]>, genome: F) -> f32
+        where F: FnOnce() -> f32
+    {
+        match (one, two) {
+            (Some(one), Some(two)) if self.blend >= 1.0 => Probes::<T>::behaviour_distance(one, two),
+            (Some(one), Some(two)) => self.blend * Probes::<T>::behaviour_distance(one, two) + (1.0 - self.blend) * genome(),
+            _ => genome()
+        }
+    }
+}
+
+
+
+/// the output function is a closure, so just show the probes and the blend
+impl<T> fmt::Debug for Probes<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Probes")
+            .field("inputs", &self.inputs)
+            .field("blend", &self.blend)
+            .finish()
+    }
+}
@@ -0,0 +1,54 @@
+//! Small helpers for sampling from distributions which aren't 
+//! included in rand by default. These are shared by the continuous optimizers 
+//! and any genome which needs gaussian noise
+
+extern crate rand;
+
+use std::f32::consts::PI;
+use rand::Rng;
+
+
+
+/// sample a number from the standard normal distribution N(0, 1) 
+/// using the Box-Muller transform
+#[inline]
+pub fn gaussian<R: Rng + ?Sized>(r: &mut R) -> f32 {
+    // gen::<f32>() is in [0, 1), shift it to (0, 1] so the log is always defined
+    let one = 1.0 - r.gen::<f32>();
+    let two = r.gen::<f32>();
+    (-2.0 * one.ln()).sqrt() * (2.0 * PI * two).cos()
+}
+
+
+
+/// sample a number from a normal distribution with a given mean and standard deviation
+#[inline]
+pub fn normal<R: Rng + ?Sized>(r: &mut R, mean: f32, std_dev: f32) -> f32 {
+    mean + std_dev * gaussian(r)
+}
+
+
+
+/// Mix a seed with two other numbers into a new well spread seed (splitmix64). This is used to
+/// give every member of every generation its own reproducible seed from a single population seed
+#[inline]
+pub fn mix(seed: u64, one: u64, two: u64) -> u64 {
+    let mut z = seed
+        .wrapping_add(one.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+        .wrapping_add(two.wrapping_mul(0xBF58_476D_1CE4_E5B9));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+
+
+/// The cumulative distribution function of the standard normal distribution, using the
+/// Abramowitz and Stegun approximation of the error function (good to about 1e-7)
+pub fn normal_cdf(x: f32) -> f32 {
+    let z = x.abs() / 2.0_f32.sqrt();
+    let t = 1.0 / (1.0 + 0.327_591_1 * z);
+    let poly = t * (0.254_829_6 + t * (-0.284_496_7 + t * (1.421_413_8 + t * (-1.453_152 + t * 1.061_405_4))));
+    let erf = 1.0 - poly * (-z * z).exp();
+    if x >= 0.0 { 0.5 * (1.0 + erf) } else { 0.5 * (1.0 - erf) }
+}
@@ -0,0 +1,139 @@
+//! The record of a finished run. Population::run hands this back with the champion so the
+//! fitness curve, the species at the end of the run, and how much work it took don't have
+//! to be logged by hand from the run function. CMA-ES and differential evolution hand back
+//! the same report, they don't have an environment or species so those are left empty. It is serializable so runs can be archived as JSON.
+
+extern crate serde_json;
+
+use std::fs::File;
+use std::error::Error;
+use std::time::Duration;
+use uuid::Uuid;
+use serde::Serialize;
+use super::{
+    genome::Genome,
+    generation::Generation,
+    stats::GenerationStats
+};
+
+
+
+/// Why the run stopped
+///
+/// Satisfied - the run function returned true
+/// Cancelled - the population's cancel handle was set, the report holds the best member up to that point
+/// Exhausted - CMA-ES used up all of its restarts before the run function returned true
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum StopReason {
+    Satisfied,
+    Cancelled,
+    Exhausted
+}
+
+
+
+/// A species as it stood in the last generation of the run
+///
+/// id: the niche id of the species
+/// age: the number of generations the species has been alive
+/// size: the number of members in the species
+/// best: the highest fitness of a member in the species
+/// adjusted_fitness: the total adjusted fitness of the species
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeciesSummary {
+    pub id: Uuid,
+    pub age: i32,
+    pub size: usize,
+    pub best: f32,
+    pub adjusted_fitness: f32
+}
+
+
+
+/// Everything worth keeping from a run
+///
+/// champion: the best member the run returned
+/// fitness: the champion's fitness
+/// environment: the environment at the end of the run
+/// history: the stats of every generation trained
+/// evaluations: the number of members solved by the problem, members predicted by a surrogate are not counted
+/// elapsed: the wall clock time of the run
+/// stop: why the run stopped
+/// species: the species of the last generation, for age layers the species of every layer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunReport<T, E> {
+    pub champion: T,
+    pub fitness: f32,
+    pub environment: E,
+    pub history: Vec<GenerationStats>,
+    pub evaluations: usize,
+    pub elapsed: Duration,
+    pub stop: StopReason,
+    pub species: Vec<SpeciesSummary>
+}
+
+
+
+impl SpeciesSummary {
+
+    /// summarize the species of a generation, this should be called after it has been speciated
+    pub fn from_generation<T, E>(gen: &Generation<T, E>) -> Vec<Self>
+        where
+            T: Genome<T, E> + Send + Sync,
+            E: Send + Sync
+    {
+        gen.species
+            .iter()
+            .map(|spec| SpeciesSummary {
+                id: spec.niche_id,
+                age: spec.age,
+                size: spec.members.len(),
+                best: spec.members
+                    .iter()
+                    .map(|x| gen.members[x.1].objective)
+                    .fold(f32::MIN, f32::max),
+                adjusted_fitness: spec.total_adjusted_fitness.unwrap_or(0.0)
+            })
+            .collect()
+    }
+}
+
+
+
+impl<T, E> RunReport<T, E> {
+
+    /// the number of generations the run trained
+    pub fn generations(&self) -> usize {
+        self.history.len()
+    }
+
+    /// the best fitness of every generation, the fitness curve of the run
+    pub fn fitness_curve(&self) -> Vec<f32> {
+        self.history.iter().map(|x| x.best).collect()
+    }
+
+    /// take the champion and the environment out of the report, this is what run used to return
+    pub fn into_parts(self) -> (T, E) {
+        (self.champion, self.environment)
+    }
+}
+
+
+
+impl<T, E> RunReport<T, E>
+    where
+        T: Serialize,
+        E: Serialize
+{
+
+    /// export the report to a json string
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&self)
+    }
+
+    /// save the report as json to a file path
+    pub fn save_json(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
+        serde_json::to_writer(&File::create(file_path)?, &self)?;
+        Ok(())
+    }
+}
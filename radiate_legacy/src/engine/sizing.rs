@@ -0,0 +1,94 @@
+//! How big the population is from generation to generation, and when to throw it away
+//! and start over. A fixed size is the default, otherwise the size can grow when the run
+//! stagnates (more members to search with) and shrink as the fitness of the population
+//! converges (fewer members wasted on the same answer). A restart starts the population over
+//! from brand new members when it stagnates - IPOP restarts double the size every time it does.
+//! The hall of fame lives through restarts so nothing found before one is lost.
+
+use super::stats::GenerationStats;
+
+
+
+/// How the size of the population changes between generations
+///
+/// Fixed - the size never changes
+/// Adaptive - multiply the size by grow every stagnation number of generations without an improvement,
+///            and by shrink every generation the standard deviation of the fitness is under converged.
+///            The size always stays between min and max
+/// Schedule - a function of the index of the next generation and the stats of the last one which returns the size
+#[derive(Debug, Clone)]
+pub enum Sizing {
+    Fixed,
+    Adaptive { stagnation: usize, grow: f32, converged: f32, shrink: f32, min: i32, max: i32 },
+    Schedule(fn(usize, &GenerationStats) -> i32)
+}
+
+
+
+/// When and how the population starts over
+///
+/// Fresh - start over with new members at the same size after stagnation number of generations without an improvement
+/// Ipop - start over like fresh but multiply the size by factor every restart, up to max_size
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Restart {
+    Fresh { stagnation: usize },
+    Ipop { stagnation: usize, factor: f32, max_size: i32 }
+}
+
+
+
+impl Sizing {
+
+    /// the default adaptive sizing, grow by half after 10 stagnant generations and shrink by
+    /// a tenth while converged, never going under a tenth or over ten times the starting size
+    pub fn adaptive(size: i32) -> Self {
+        Sizing::Adaptive {
+            stagnation: 10,
+            grow: 1.5,
+            converged: 1e-3,
+            shrink: 0.9,
+            min: (size / 10).max(2),
+            max: size * 10
+        }
+    }
+
+    /// The size of the next generation given the current size, the stats of the generation which was
+    /// just trained, and the number of generations since the best fitness of the run last improved
+    pub fn next_size(&self, size: i32, stats: &GenerationStats, stagnant: usize) -> i32 {
+        match self {
+            Self::Fixed => size,
+            Self::Adaptive { stagnation, grow, converged, shrink, min, max } => {
+                let next = if *stagnation > 0 && stagnant > 0 && stagnant % *stagnation == 0 {
+                    size as f32 * grow
+                } else if stats.std_dev < *converged {
+                    size as f32 * shrink
+                } else {
+                    size as f32
+                };
+                (next.round() as i32).max(*min).min((*max).max(*min))
+            },
+            Self::Schedule(schedule) => schedule(stats.generation + 1, stats).max(1)
+        }
+    }
+}
+
+
+
+impl Restart {
+
+    /// the number of generations without an improvement before the population starts over
+    pub fn stagnation(&self) -> usize {
+        match self {
+            Self::Fresh { stagnation } => *stagnation,
+            Self::Ipop { stagnation, .. } => *stagnation
+        }
+    }
+
+    /// the size of the population after a restart
+    pub fn restart_size(&self, size: i32) -> i32 {
+        match self {
+            Self::Fresh { .. } => size,
+            Self::Ipop { factor, max_size, .. } => ((size as f32 * factor).round() as i32).min(*max_size)
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Summary statistics of a generation. These are handed to the problem between
+//! generations so it can decide if it should change, and kept by the population
+//! so the state of the run can be looked at without digging through the members.
+
+use super::{
+    genome::Genome,
+    generation::{Generation, Container}
+};
+
+
+
+/// The summary of a single generation
+///
+/// generation: the index of the generation
+/// size: the number of members the problem solved, members given a fitness by a surrogate model are left out
+/// species: the number of species after speciation
+/// best: the highest fitness in the generation
+/// mean: the mean fitness of the generation
+/// worst: the lowest fitness in the generation
+/// std_dev: the standard deviation of the fitness of the generation
+/// feasible: the number of members which don't violate the problem's constraints, if any do then only these are in the fitness
+/// diversity: the mean distance between the members whose distances were cached, 0 if none were
+/// repairs: the number of children bred into the generation which were invalid and repaired
+/// discards: the number of children which were invalid, couldn't be repaired, and were thrown away
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationStats {
+    pub generation: usize,
+    pub size: usize,
+    pub species: usize,
+    pub best: f32,
+    pub mean: f32,
+    pub worst: f32,
+    pub std_dev: f32,
+    pub diversity: f32,
+    #[serde(default)]
+    pub repairs: usize,
+    #[serde(default)]
+    pub discards: usize,
+    #[serde(default)]
+    pub feasible: usize
+}
+
+
+
+impl GenerationStats {
+
+    /// summarize a list of fitness scores
+    pub fn from_scores(generation: usize, species: usize, scores: &[f32]) -> Self {
+        if scores.is_empty() {
+            return GenerationStats { generation, species, ..Default::default() };
+        }
+        let size = scores.len();
+        let mean = scores.iter().sum::<f32>() / size as f32;
+        let variance = scores.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / size as f32;
+        GenerationStats {
+            generation,
+            size,
+            species,
+            best: scores.iter().cloned().fold(f32::MIN, f32::max),
+            mean,
+            worst: scores.iter().cloned().fold(f32::MAX, f32::min),
+            std_dev: variance.sqrt(),
+            diversity: 0.0,
+            repairs: 0,
+            discards: 0,
+            feasible: size
+        }
+    }
+
+    /// summarize the members of a generation, this should be called after it has been optimized and speciated.
+    /// Members are summarized by the problem's fitness, not the score they were given for selection
+    pub fn from_generation<T, E>(gen: &Generation<T, E>) -> Self
+        where
+            T: Genome<T, E> + Send + Sync,
+            E: Send + Sync
+    {
+        let mut stats = GenerationStats::from_members(gen.index, gen.species.len(), gen.members.iter());
+        stats.diversity = gen.distances.mean();
+        stats.repairs = gen.repairs;
+        stats.discards = gen.discards;
+        stats
+    }
+
+    /// summarize several generations as one, this is how age layers are summarized
+    pub fn from_generations<T, E>(gens: &[Generation<T, E>]) -> Self
+        where
+            T: Genome<T, E> + Send + Sync,
+            E: Send + Sync
+    {
+        let species = gens.iter().map(|x| x.species.len()).sum();
+        let generation = gens.first().map(|x| x.index).unwrap_or(0);
+        let mut stats = GenerationStats::from_members(generation, species, gens.iter().flat_map(|gen| gen.members.iter()));
+        stats.repairs = gens.iter().map(|x| x.repairs).sum();
+        stats.discards = gens.iter().map(|x| x.discards).sum();
+        let cached = gens.iter().filter(|x| !x.distances.is_empty()).collect::<Vec<_>>();
+        if !cached.is_empty() {
+            stats.diversity = cached.iter().map(|x| x.distances.mean()).sum::<f32>() / cached.len() as f32;
+        }
+        stats
+    }
+
+    /// Summarize the members the problem solved by their fitness, members who were only given a predicted fitness
+    /// by a surrogate model don't count. If any of them are feasible only those are summarized, so a member which
+    /// breaks the constraints never shows up as the best with a made up score
+    fn from_members<'a, T, E, I>(generation: usize, species: usize, members: I) -> Self
+        where
+            T: Genome<T, E> + Send + Sync + 'a,
+            E: Send + Sync + 'a,
+            I: Iterator<Item = &'a Container<T, E>>
+    {
+        let members = members.filter(|x| !x.predicted).collect::<Vec<_>>();
+        let feasible = members.iter().filter(|x| x.is_feasible()).count();
+        let scores = members.iter()
+            .filter(|x| feasible == 0 || x.is_feasible())
+            .map(|x| x.objective)
+            .collect::<Vec<_>>();
+        let mut stats = GenerationStats::from_scores(generation, species, &scores);
+        stats.size = members.len();
+        stats.feasible = feasible;
+        stats
+    }
+}
+
+
+
+/// The q quantile of a list of values (0.5 is the median) interpolating between the two closest
+/// values, or 0 if the list is empty. The values don't need to be sorted
+pub fn quantile(values: &[f32], q: f32) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let position = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f32;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f32)
+}
+
+
+
+/// the median of a list of values, or 0 if the list is empty
+pub fn median(values: &[f32]) -> f32 {
+    quantile(values, 0.5)
+}
+
+
+
+/// the fraction of runs which were a success, or 0 if there weren't any runs
+pub fn success_rate<I>(successes: I) -> f32
+    where I: Iterator<Item = bool>
+{
+    let (hits, total) = successes.fold((0, 0), |(hits, total), x| (hits + x as usize, total + 1));
+    hits as f32 / total.max(1) as f32
+}
@@ -0,0 +1,223 @@
+//! Surrogate assisted evaluation. For problems where every call to solve is expensive, a cheap
+//! model is fit to the past evaluations and used to screen each new generation before it is
+//! solved. Only the members the model thinks are the most promising are sent to the real problem,
+//! the rest are given the fitness the model predicts for them. The model works on a feature vector
+//! of the genome given by the user, so it knows nothing about the genome itself.
+
+extern crate rayon;
+
+use std::sync::Arc;
+use std::collections::VecDeque;
+use rayon::prelude::*;
+use super::{
+    genome::Genome,
+    generation::Container
+};
+
+
+
+/// turn a genome into the feature vector the surrogate model is fit on
+pub type FeatureFn<T> = Arc<dyn Fn(&T) -> Vec<f32> + Send + Sync>;
+
+
+
+/// The model used to predict the fitness of a member from its features
+///
+/// Knn - the inverse distance weighted mean fitness of the k nearest past evaluations
+/// Rbf - gaussian radial basis function regression with the given width, every past
+///       evaluation is weighted by how close it is to the member
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Model {
+    Knn(usize),
+    Rbf(f32)
+}
+
+
+
+/// A surrogate model of the problem
+///
+/// model: how the fitness is predicted
+/// fraction: the fraction of each generation which is sent to the real problem
+/// warmup: the number of real evaluations needed before the model is trusted, until then everyone is solved
+/// capacity: the most past evaluations to keep, the oldest are dropped first
+/// evaluations: the number of members which have been solved by the real problem
+/// predictions: the number of members which have been given a predicted fitness instead
+pub struct Surrogate<T> {
+    pub model: Model,
+    pub fraction: f32,
+    pub warmup: usize,
+    pub capacity: usize,
+    pub evaluations: usize,
+    pub predictions: usize,
+    features: FeatureFn<T>,
+    samples: VecDeque<(Vec<f32>, f32)>
+}
+
+
+
+impl<T> Surrogate<T> {
+
+    /// Create a surrogate with the function that gives the features of a member.
+    /// By default it is a 5 nearest neighbor model which solves the top 25% of each generation
+    pub fn new<F>(features: F) -> Self
+        where F: Fn(&T) -> Vec<f32> + Send + Sync + 'static
+    {
+        Surrogate {
+            model: Model::Knn(5),
+            fraction: 0.25,
+            warmup: 100,
+            capacity: 5000,
+            evaluations: 0,
+            predictions: 0,
+            features: Arc::new(features),
+            samples: VecDeque::new()
+        }
+    }
+
+    /// set the model used to predict fitness
+    pub fn model(mut self, model: Model) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// set the fraction of each generation which is sent to the real problem
+    pub fn fraction(mut self, fraction: f32) -> Self {
+        self.fraction = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// set the number of real evaluations needed before the model starts screening
+    pub fn warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// set the most past evaluations to keep
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// the number of past evaluations the model is fit on
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// true if the model has nothing to be fit on
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// forget every past evaluation, this needs to happen whenever the problem changes
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// remember a real evaluation
+    pub fn record(&mut self, features: Vec<f32>, fitness: f32) {
+        if self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((features, fitness));
+    }
+
+    /// Predict the fitness of a set of features from the past evaluations,
+    /// None if there are no past evaluations to go off of
+    pub fn predict(&self, features: &[f32]) -> Option<f32> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let mut distances = self.samples.iter()
+            .map(|(x, fit)| (Surrogate::<T>::distance(x, features), *fit))
+            .collect::<Vec<_>>();
+        // an exact match is the best prediction there is
+        if let Some((_, fit)) = distances.iter().find(|(dist, _)| *dist == 0.0) {
+            return Some(*fit);
+        }
+        match self.model {
+            Model::Knn(k) => {
+                distances.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+                distances.truncate(k.max(1));
+                Some(Surrogate::<T>::weighted_mean(distances.iter().map(|(dist, fit)| (1.0 / dist, *fit))))
+            },
+            Model::Rbf(width) => {
+                let width = 2.0 * width.powi(2);
+                let weights = distances.iter()
+                    .map(|(dist, fit)| ((-dist.powi(2) / width).exp(), *fit))
+                    .collect::<Vec<_>>();
+                // too far from everything for the kernel to say anything, go with the nearest
+                if weights.iter().all(|(w, _)| *w <= 0.0) {
+                    return distances.iter()
+                        .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+                        .map(|(_, fit)| *fit);
+                }
+                Some(Surrogate::<T>::weighted_mean(weights.into_iter()))
+            }
+        }
+    }
+
+    /// predict the fitness of a member from its features
+    pub fn predict_member(&self, member: &T) -> Option<f32> {
+        self.predict(&(self.features)(member))
+    }
+
+    /// Screen the members before they are solved. If the model has had enough evaluations to be
+    /// trusted, the fittest fraction by prediction are left to be solved and the rest are marked as
+    /// predicted and given their predicted fitness. Otherwise everyone is left to be solved
+    pub fn screen<E>(&mut self, members: &mut [Container<T, E>])
+        where
+            T: Genome<T, E> + Send + Sync,
+            E: Send + Sync
+    {
+        for cont in members.iter_mut() {
+            cont.predicted = false;
+        }
+        if self.samples.len() < self.warmup || members.is_empty() {
+            return;
+        }
+        let predictions = members.par_iter()
+            .map(|cont| self.predict(&(self.features)(&cont.member)).unwrap_or(0.0))
+            .collect::<Vec<_>>();
+        let mut ranked = (0..members.len()).collect::<Vec<_>>();
+        ranked.sort_by(|a, b| predictions[*b].partial_cmp(&predictions[*a]).unwrap());
+        let keep = ((members.len() as f32 * self.fraction).ceil() as usize).max(1);
+        for index in ranked.into_iter().skip(keep) {
+            members[index].predicted = true;
+            members[index].fitness_score = predictions[index];
+        }
+    }
+
+    /// Fit the model on the members of a generation which were solved by the real problem
+    pub fn learn<E>(&mut self, members: &[Container<T, E>])
+        where
+            T: Genome<T, E> + Send + Sync,
+            E: Send + Sync
+    {
+        for cont in members.iter() {
+            if cont.predicted {
+                self.predictions += 1;
+            } else {
+                let features = (self.features)(&cont.member);
+                self.record(features, cont.objective);
+                self.evaluations += 1;
+            }
+        }
+    }
+
+    /// euclidean distance between two feature vectors
+    fn distance(one: &[f32], two: &[f32]) -> f32 {
+        one.iter()
+            .zip(two.iter())
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    /// the mean of the fitness scores weighted by their weights
+    fn weighted_mean<I>(weights: I) -> f32
+        where I: Iterator<Item = (f32, f32)>
+    {
+        let (total, weight) = weights.fold((0.0, 0.0), |(total, weight), (w, fit)| (total + w * fit, weight + w));
+        total / weight
+    }
+}
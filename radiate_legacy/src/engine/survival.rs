@@ -2,22 +2,29 @@
 extern crate rayon;
 extern crate rand;
 
-use std::sync::{Arc, RwLock};
 use rand::Rng;
 use rand::rngs::ThreadRng;
-use rand::seq::SliceRandom;
+use std::sync::{Arc, RwLock};
 use rayon::prelude::*;
+#[allow(deprecated)]
 use super::generation::{Container, Family, Member};
+use super::niche::Niche;
 use super::genome::Genome;
 
 
 
+/// A parent picked to breed - its fitness, the index of its species in the list of
+/// species it was picked from, and the index of the member in its generation
+pub type Parent = (f32, usize, usize);
+
+
+
 //////////////////////////////////////////////////////////////////////////////////////////
-/// Note these should not be directly exposed to the user as to avoid confusion with   ///
-/// too many knobs to turn to create a population. Instead, provide functions to add   ///
-/// them and defaults if they are not added. These are not necessarily needed options, ///
-/// they are add-ons and really only for if you really want to test around with your   ///
-/// structure that is evolving, provides users with more options which is always good  ///
+//  Note these should not be directly exposed to the user as to avoid confusion with    //
+//  too many knobs to turn to create a population. Instead, provide functions to add    //
+//  them and defaults if they are not added. These are not necessarily needed options,  //
+//  they are add-ons and really only for if you really want to test around with your    //
+//  structure that is evolving, provides users with more options which is always good   //
 //////////////////////////////////////////////////////////////////////////////////////////
 
 
@@ -49,10 +56,13 @@ pub enum ParentalCriteria {
     BiasedRandom,
     /// Only the best in each species are allowed to reproduce
     BestInSpecies,
+    /// Pick one parent the same way as BiasedRandom, then pick the given number of
+    /// candidates the same way and use the one most different from the first parent
+    /// as the other parent. This uses the generation's distance cache when it can.
+    /// Note this could lead to large expansion in population
+    MostDifferent(usize)
     // Not implemented:
     // OnlySurvivors - those who survive are only allowed to reproduce
-    // MostDifferent - Pick one parent, then find the parent most different from it (structurally)
-    //                 and use that as the other parent. Note this could lead to large expansion in population
 }
 
 
@@ -61,9 +71,10 @@ pub enum ParentalCriteria {
 impl SurvivalCriteria {
 
 
-    /// Based on the survival criteria, given a vec of containers and families, pick who survives
+    /// Based on the survival criteria, given a slice of containers and families, pick who survives.
+    /// The survivors are returned as their index in the members
     #[inline]
-    pub fn pick_survivors<T, E>(&self, members: &mut [Container<T, E>], families: &[Family<T, E>]) -> Option<Vec<Arc<RwLock<T>>>>
+    pub fn survivors<T, E>(&self, members: &[Container<T, E>], families: &[Niche<T, E>]) -> Option<Vec<usize>>
         where
             T: Genome<T, E> + Send + Sync + Clone,
             E: Send + Sync
@@ -71,7 +82,7 @@ impl SurvivalCriteria {
         match self {
             Self::Fittest => {
                 Some(families.par_iter()
-                    .map(|x| x.read().unwrap().fittest().1)
+                    .map(|x| x.fittest().1)
                     .collect::<Vec<_>>())
             },
             Self::TopNumber(num) => {
@@ -84,10 +95,31 @@ impl SurvivalCriteria {
         }
     }
 
-    #[deprecated = "Use `pick_survivors`"]
+    /// Pick who survives from species shared behind locks, the way generations used to hold them.
+    /// The survivors are copies of the members behind new locks, the families have to be the species of the members
+    #[deprecated = "Use `survivors` for the index of each survivor in the members"]
+    #[allow(deprecated)]
+    #[inline]
+    pub fn pick_survivors<T, E>(&self, members: &mut [Container<T, E>], families: &[Family<T, E>]) -> Option<Vec<Member<T>>>
+        where
+            T: Genome<T, E> + Send + Sync + Clone,
+            E: Send + Sync
+    {
+        let survivors = match self {
+            Self::Fittest => families.iter().map(|x| x.read().unwrap().fittest().1).collect(),
+            _ => self.survivors(members, &[])?
+        };
+        Some(survivors
+            .into_iter()
+            .map(|i| Arc::new(RwLock::new(members[i].member.clone())))
+            .collect())
+    }
+
+    #[deprecated = "Use `survivors`"]
+    #[allow(deprecated)]
     #[doc(hidden)]
     #[inline]
-    pub fn pick_survivers<T, E>(&self, members: &mut [Container<T, E>], families: &[Family<T, E>]) -> Option<Vec<Arc<RwLock<T>>>>
+    pub fn pick_survivers<T, E>(&self, members: &mut [Container<T, E>], families: &[Family<T, E>]) -> Option<Vec<Member<T>>>
         where
             T: Genome<T, E> + Send + Sync + Clone,
             E: Send + Sync
@@ -98,21 +130,20 @@ impl SurvivalCriteria {
 
 
     /// TopNumber and TopPercent are basically the same so this function does the job of both of them,
-    /// just convert the percent to a number before calling the function
+    /// just convert the percent to a number before calling the function. The members are left where
+    /// they are, only their indexes are sorted
     #[inline]
-    fn get_top_num<T, E>(num_to_keep: usize, members: &mut [Container<T, E>]) -> Option<Vec<Arc<RwLock<T>>>>
+    fn get_top_num<T, E>(num_to_keep: usize, members: &[Container<T, E>]) -> Option<Vec<usize>>
         where
             T: Genome<T, E> + Send + Sync + Clone,
             E: Send + Sync
     {
-        members
-            .par_sort_by(|a, b| {
-                b.fitness_score.partial_cmp(&a.fitness_score).unwrap()
-            });
-        Some((0..num_to_keep)
-            .into_iter()
-            .map(|i| Arc::clone(&members[i].member))
-            .collect())
+        let mut ranked = (0..members.len()).collect::<Vec<_>>();
+        ranked.par_sort_by(|a, b| {
+            members[*b].fitness_score.partial_cmp(&members[*a].fitness_score).unwrap()
+        });
+        ranked.truncate(num_to_keep);
+        Some(ranked)
     }
 
 }
@@ -124,33 +155,79 @@ impl SurvivalCriteria {
 impl ParentalCriteria {
 
 
-    /// Find two parents to crossover and produce a child
+    /// Find two parents to crossover and produce a child. Without a way to measure the distance between
+    /// parents MostDifferent can't tell the candidates apart, so it picks the first one
     #[inline]
-    pub fn pick_parents<T, E>(&self, inbreed_rate: f32, families: &[Family<T, E>]) -> Option<((f32, Member<T>), (f32, Member<T>))>
+    pub fn pick_parents<T, E>(&self, inbreed_rate: f32, families: &[&Niche<T, E>]) -> Option<(Parent, Parent)>
         where
             T: Genome<T, E> + Send + Sync + Clone,
             E: Send + Sync 
+    {
+        self.pick_parents_with(inbreed_rate, families, |_, _| 0.0)
+    }
+
+
+
+    /// Find two parents to crossover and produce a child, the distance function gives the distance between
+    /// two picked parents and is only used by criteria which care how different the parents are
+    #[inline]
+    pub fn pick_parents_with<T, E, F>(&self, inbreed_rate: f32, families: &[&Niche<T, E>], distance: F) -> Option<(Parent, Parent)>
+        where
+            T: Genome<T, E> + Send + Sync + Clone,
+            E: Send + Sync,
+            F: Fn(&Parent, &Parent) -> f32
     {
         match self {
             Self::BiasedRandom => {
-                return Some(self.create_match(inbreed_rate, families))
+                return self.create_match(inbreed_rate, families)
             },
             Self::BestInSpecies => {
                 let mut r = rand::thread_rng();
-                let child_one = families.choose(&mut r)?.read().unwrap().fittest();
-                let child_two = families.choose(&mut r)?.read().unwrap().fittest();
-                return Some((child_one, child_two))
+                if families.is_empty() {
+                    return None;
+                }
+                let (one, two) = (r.gen_range(0, families.len()), r.gen_range(0, families.len()));
+                let (fit_one, child_one) = families[one].fittest();
+                let (fit_two, child_two) = families[two].fittest();
+                return Some(((fit_one, one, child_one), (fit_two, two, child_two)))
+            },
+            Self::MostDifferent(candidates) => {
+                let mut r = rand::thread_rng();
+                let one = self.get_biased_random_parent(&mut r, families)?;
+                let mut two = None;
+                for _ in 0..(*candidates).max(1) {
+                    let candidate = self.get_biased_random_parent(&mut r, families)?;
+                    let between = distance(&one, &candidate);
+                    if two.map_or(true, |(most, _)| between > most) {
+                        two = Some((between, candidate));
+                    }
+                }
+                Some((one, two?.1))
             }
         }
     }
 
 
 
+    /// pick a single parent, a biased random species then a biased random member of it
+    #[inline]
+    fn get_biased_random_parent<T, E>(&self, r: &mut ThreadRng, families: &[&Niche<T, E>]) -> Option<Parent>
+        where
+            T: Genome<T, E> + Send + Sync + Clone,
+            E: Send + Sync
+    {
+        let species = self.get_biased_random_species(r, families)?;
+        let (fitness, member) = self.get_biased_random_member(r, families[species]);
+        Some((fitness, species, member))
+    }
+
+
+
     /// pick two parents to breed a child - these use biased random ways of picking 
-    /// parents and returns a tuple of tuples where the f32 is the parent's fitness,
-    /// and the type is the parent itself
+    /// parents and returns a tuple of parents where the f32 is the parent's fitness,
+    /// then the index of its species and the index of the parent itself
     #[inline]
-    fn create_match<T, E>(&self, inbreed_rate: f32, families: &[Family<T, E>]) -> ((f32, Member<T>), (f32, Member<T>))
+    fn create_match<T, E>(&self, inbreed_rate: f32, families: &[&Niche<T, E>]) -> Option<(Parent, Parent)>
         where
             T: Genome<T, E> + Send + Sync + Clone,
             E: Send + Sync
@@ -159,18 +236,17 @@ impl ParentalCriteria {
         let (species_one, species_two);
         // get two species to pick from taking into account an inbreeding rate - an inbreed can happen without this 
         if r.gen::<f32>() < inbreed_rate {
-            let temp = self.get_biased_random_species(&mut r, families).unwrap();
-            species_one = Arc::clone(&temp);
-            species_two = temp;
+            species_one = self.get_biased_random_species(&mut r, families)?;
+            species_two = species_one;
         } else {
-            species_one = self.get_biased_random_species(&mut r, families).unwrap();
-            species_two = self.get_biased_random_species(&mut r, families).unwrap();
+            species_one = self.get_biased_random_species(&mut r, families)?;
+            species_two = self.get_biased_random_species(&mut r, families)?;
         }
         // get two parents from the species, again the parent may be the same 
-        let parent_one = self.get_biased_random_member(&mut r, &species_one);
-        let parent_two = self.get_biased_random_member(&mut r, &species_two);
+        let (fit_one, parent_one) = self.get_biased_random_member(&mut r, families[species_one]);
+        let (fit_two, parent_two) = self.get_biased_random_member(&mut r, families[species_two]);
         // return the parent tuples
-        (parent_one, parent_two)
+        Some(((fit_one, species_one, parent_one), (fit_two, species_two, parent_two)))
     }
 
 
@@ -182,7 +258,7 @@ impl ParentalCriteria {
     /// Statistically this allows for species with larger adjusted fitnesses to
     /// have a greater change of being picked for breeding
     #[inline]
-    fn get_biased_random_species<T, E>(&self, r: &mut ThreadRng, families: &[Family<T, E>]) -> Option<Family<T, E>>
+    fn get_biased_random_species<T, E>(&self, r: &mut ThreadRng, families: &[&Niche<T, E>]) -> Option<usize>
         where 
             T: Genome<T, E> + Send + Sync + Clone,
             E: Send + Sync
@@ -192,42 +268,42 @@ impl ParentalCriteria {
         let mut result = None;
         let total = families.iter()
             .fold(0.0, |sum, curr| {
-                sum + (*curr).read().unwrap().get_total_adjusted_fitness()
+                sum + curr.get_total_adjusted_fitness()
             });
 
         // iterate through the species until the iterative sum is at or above the selected
         // random adjusted fitness level
         let mut curr = 0.0;
         let index = r.gen::<f32>() * total;
-        for i in families.iter() {
-            curr += i.read().ok()?.get_total_adjusted_fitness();
+        for (i, family) in families.iter().enumerate() {
+            curr += family.get_total_adjusted_fitness();
             if curr >= index {
-                result = Some(Arc::clone(i));
+                result = Some(i);
                 break
             }
         }
-        // either return the result, or panic!
-        result.or_else(|| Some(Arc::clone(families.first()?)))
+        // either return the result, or the first species if there is one
+        result.or(if families.is_empty() { None } else { Some(0) })
     }
 
 
 
     /// Get a biased random member from the species. By summing the fitness scores of the 
-    /// members, members with larger fitness scores are statistically more likely to be picked
+    /// members, members with larger fitness scores are statistically more likely to be picked.
+    /// Returns the member's fitness and its index in the generation
     #[inline]
-    pub fn get_biased_random_member<T, E>(&self, r: &mut ThreadRng, family: &Family<T, E>) -> (f32, Member<T>)
+    pub fn get_biased_random_member<T, E>(&self, r: &mut ThreadRng, family: &Niche<T, E>) -> (f32, usize)
         where
             T: Genome<T, E> + Send + Sync + Clone,
             E: Send + Sync
     {
         // declare a result which will panic! at the end of the function if there 
         // is no member found, then get the species total fitness score
-        let species_lock = family.read().unwrap();
-        let total = species_lock.get_total_adjusted_fitness();
+        let total = family.get_total_adjusted_fitness();
         let index = r.gen::<f32>() * total;
         let (mut result, mut curr) = (None, 0.0);
         // go through each member and see if it's adjusted fitness has pushed it over the edge
-        for member in species_lock.members.iter() {
+        for member in family.members.iter() {
             curr += member.0;
             if curr >= index {
                 result = Some(member);
@@ -238,14 +314,9 @@ impl ParentalCriteria {
         // negative, just take the first member. If the fitness of the species is negative,
         // the algorithm essentially preforms a random search for these biased functions 
         // once the fitness is above 0, it will 'catch on' and start producing biased results
-        result.or_else(|| Some(&species_lock.members[0]))
-            .and_then(|val| {
-                Some((val.0, val.1.clone()
-                    .upgrade()
-                    .unwrap_or_else(|| panic!("Failed to get random species member."))
-                ))
-            })
-            .unwrap()
+        result.or_else(|| family.members.first())
+            .map(|val| (val.0, val.1))
+            .unwrap_or_else(|| panic!("Failed to get random species member."))
     }
 
 }
@@ -0,0 +1,472 @@
+//! Hyperparameter sweeps over the population's config and the genome's environment.
+//! A sweep is a list of parameters, each with a range of values and a function to set
+//! the value into a setting (a config and environment pair). Settings are searched by grid
+//! search, random search, or an outer evolutionary loop, each setting is run a number of times,
+//! and the results are aggregated into a report which can be saved as CSV.
+
+extern crate rand;
+
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::error::Error;
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::sync::Arc;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use super::{
+    genome::Genome,
+    problem::Problem,
+    environment::Envionment,
+    population::{Population, Config},
+    stats,
+    random
+};
+
+
+
+/// A single setting to run, the config for the population and the environment for the genome
+#[derive(Debug, Clone)]
+pub struct Setting<E> {
+    pub config: Config,
+    pub environment: E
+}
+
+
+
+/// The range of values a parameter can take
+///
+/// Values - a fixed list of values
+/// Uniform - anywhere between a lower and upper bound, grid search splits this into evenly spaced steps
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Range {
+    Values(Vec<f32>),
+    Uniform(f32, f32)
+}
+
+
+
+/// How the settings are searched
+///
+/// Grid - every combination of the parameter values
+/// Random - a number of randomly sampled settings
+/// Evolutionary - evolve the settings themselves, a population of the given size for the given number of
+///                generations where each setting's fitness is the mean best fitness of its runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Search {
+    Grid,
+    Random(usize),
+    Evolutionary { size: usize, generations: usize }
+}
+
+
+
+/// The result of a single run of a setting, the best fitness found and the
+/// number of generations it took to hit the target if it ever did
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trial {
+    pub best: f32,
+    pub generations: Option<usize>
+}
+
+
+
+/// A row of the report, the parameter values of a setting and the aggregated results of its runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SweepRow {
+    pub values: Vec<(String, f32)>,
+    pub trials: Vec<Trial>
+}
+
+
+
+/// The results of every setting in a sweep
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SweepReport {
+    pub rows: Vec<SweepRow>
+}
+
+
+
+/// A function which sets a parameter's value into a setting
+pub type SetFn<E> = Arc<dyn Fn(&mut Setting<E>, f32) + Send + Sync>;
+
+
+
+/// A named parameter to sweep over
+pub struct Parameter<E> {
+    pub name: String,
+    pub range: Range,
+    set: SetFn<E>
+}
+
+
+
+/// The sweep itself, the base setting every parameter is set into and how to search the parameters
+pub struct Sweep<E> {
+    base: Setting<E>,
+    parameters: Vec<Parameter<E>>,
+    search: Search,
+    repeats: usize,
+    steps: usize,
+    debug_progress: bool,
+    invalid: Option<&'static str>
+}
+
+
+
+impl<E> Setting<E>
+    where E: Envionment + Clone + Send + Sync + Default
+{
+
+    /// Evolve a population with base members under this setting until the best member hits
+    /// the target fitness or the max number of generations is reached. The population is seeded
+    /// with the run's seed. This is the usual thing to call from the function given to Sweep::run
+    pub fn evolve<T, P>(&self, problem: P, size: i32, generations: usize, target: f32, seed: u64) -> Trial
+        where
+            T: Genome<T, E> + Clone + Send + Sync + Debug + PartialEq,
+            P: Problem<T> + Send + Sync
+    {
+        let best = Cell::new(f32::MIN);
+        let reached = Cell::new(None);
+        let result = Population::<T, E, P>::new()
+            .size(size)
+            .constrain(self.environment.clone())
+            .populate_base()
+            .configure(self.config.clone())
+            .impose(problem)
+            .seed(seed)
+            .run(|_, fit, num| {
+                best.set(best.get().max(fit));
+                if fit >= target {
+                    reached.set(Some(num as usize));
+                }
+                fit >= target || num as usize + 1 >= generations
+            });
+        Trial {
+            best: if result.is_ok() { best.get() } else { f32::MIN },
+            generations: reached.get()
+        }
+    }
+}
+
+
+
+impl Range {
+
+    /// Check the range can be searched, a list needs at least one value
+    /// and a uniform range needs a lower bound below its upper bound
+    pub fn validate(&self) -> Result<(), &'static str> {
+        match self {
+            Self::Values(values) if values.is_empty() => Err("Range has no values"),
+            Self::Uniform(low, high) if low.is_nan() || high.is_nan() || low >= high => Err("Uniform range needs a lower bound below its upper bound"),
+            _ => Ok(())
+        }
+    }
+
+    /// the values of the range used by grid search
+    fn grid(&self, steps: usize) -> Vec<f32> {
+        match self {
+            Self::Values(values) => values.clone(),
+            Self::Uniform(low, high) => {
+                if steps < 2 {
+                    return vec![(low + high) / 2.0];
+                }
+                (0..steps).map(|i| low + (high - low) * i as f32 / (steps - 1) as f32).collect()
+            }
+        }
+    }
+
+    /// a random value from the range
+    fn sample<R: Rng>(&self, r: &mut R) -> f32 {
+        match self {
+            Self::Values(values) => *values.choose(r).unwrap_or(&0.0),
+            Self::Uniform(low, high) => r.gen_range(*low, *high)
+        }
+    }
+
+    /// nudge a value within the range, for the evolutionary search
+    fn mutate<R: Rng>(&self, r: &mut R, value: f32) -> f32 {
+        match self {
+            Self::Values(_) => self.sample(r),
+            Self::Uniform(low, high) => random::normal(r, value, 0.1 * (high - low)).max(*low).min(*high)
+        }
+    }
+}
+
+
+
+impl Trial {
+
+    /// a run which never reached its target
+    pub fn new(best: f32) -> Self {
+        Trial { best, generations: None }
+    }
+}
+
+
+
+impl SweepRow {
+
+    /// the mean best fitness of the runs
+    pub fn mean_best(&self) -> f32 {
+        self.trials.iter().map(|x| x.best).sum::<f32>() / self.trials.len().max(1) as f32
+    }
+
+    /// the median best fitness of the runs, the same as an experiment's median
+    pub fn median_best(&self) -> f32 {
+        stats::median(&self.trials.iter().map(|x| x.best).collect::<Vec<_>>())
+    }
+
+    /// the fraction of runs which hit the target, the same as an experiment's success rate
+    pub fn success_rate(&self) -> f32 {
+        stats::success_rate(self.trials.iter().map(|x| x.generations.is_some()))
+    }
+
+    /// the mean number of generations it took to hit the target, only counting the runs which did
+    pub fn mean_generations(&self) -> Option<f32> {
+        let hits = self.trials.iter().filter_map(|x| x.generations).collect::<Vec<_>>();
+        if hits.is_empty() {
+            return None;
+        }
+        Some(hits.iter().sum::<usize>() as f32 / hits.len() as f32)
+    }
+}
+
+
+
+impl SweepReport {
+
+    /// the row with the highest mean best fitness
+    pub fn best(&self) -> Option<&SweepRow> {
+        self.rows
+            .iter()
+            .max_by(|a, b| a.mean_best().partial_cmp(&b.mean_best()).unwrap())
+    }
+
+    /// the report as CSV, one row per setting with a column for each parameter followed by the aggregated results
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        if let Some(first) = self.rows.first() {
+            for (name, _) in first.values.iter() {
+                out.push_str(&format!("{},", name));
+            }
+        }
+        out.push_str("repeats,mean_best,median_best,success_rate,mean_generations\n");
+        for row in self.rows.iter() {
+            for (_, value) in row.values.iter() {
+                out.push_str(&format!("{},", value));
+            }
+            out.push_str(&format!("{},{},{},{},{}\n",
+                row.trials.len(),
+                row.mean_best(),
+                row.median_best(),
+                row.success_rate(),
+                row.mean_generations().map(|x| x.to_string()).unwrap_or_default()
+            ));
+        }
+        out
+    }
+
+    /// save the report as CSV to a file path
+    pub fn save_csv(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
+        File::create(file_path)?.write_all(self.to_csv().as_bytes())?;
+        Ok(())
+    }
+}
+
+
+
+impl fmt::Display for SweepReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows.iter() {
+            let values = row.values.iter()
+                .map(|(name, value)| format!("{}={:.4}", name, value))
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(f, "{} mean( {:.4} ) median( {:.4} ) success( {:.2} ) gens( {} )",
+                values,
+                row.mean_best(),
+                row.median_best(),
+                row.success_rate(),
+                row.mean_generations().map(|x| format!("{:.1}", x)).unwrap_or_else(|| String::from("-"))
+            )?;
+        }
+        Ok(())
+    }
+}
+
+
+
+impl<E> Sweep<E>
+    where E: Clone
+{
+
+    /// create a sweep where every setting starts as a copy of the given config and environment
+    pub fn new(config: Config, environment: E) -> Self {
+        Sweep {
+            base: Setting { config, environment },
+            parameters: Vec::new(),
+            search: Search::Grid,
+            repeats: 5,
+            steps: 5,
+            debug_progress: false,
+            invalid: None
+        }
+    }
+
+    /// Add a parameter to sweep over with a function to set the value into a setting, for example
+    /// the new node rate of a neat environment: |s, v| s.environment.new_node_rate = Some(v).
+    /// If the range can't be searched (see Range::validate) the sweep won't run
+    pub fn parameter<F>(mut self, name: &str, range: Range, set: F) -> Self
+        where F: Fn(&mut Setting<E>, f32) + Send + Sync + 'static
+    {
+        if let Err(e) = range.validate() {
+            self.invalid = self.invalid.or(Some(e));
+        }
+        self.parameters.push(Parameter { name: String::from(name), range, set: Arc::new(set) });
+        self
+    }
+
+    /// sweep the config's inbreed rate
+    pub fn inbreed_rate(self, range: Range) -> Self {
+        self.parameter("inbreed_rate", range, |s, v| s.config.inbreed_rate = v)
+    }
+
+    /// sweep the config's crossover rate
+    pub fn crossover_rate(self, range: Range) -> Self {
+        self.parameter("crossover_rate", range, |s, v| s.config.crossover_rate = v)
+    }
+
+    /// sweep the config's speciation distance
+    pub fn distance(self, range: Range) -> Self {
+        self.parameter("distance", range, |s, v| s.config.distance = v)
+    }
+
+    /// sweep the config's species target, values are rounded to the nearest whole number
+    pub fn species_target(self, range: Range) -> Self {
+        self.parameter("species_target", range, |s, v| s.config.species_target = v.round().max(0.0) as usize)
+    }
+
+    /// how the settings are searched
+    pub fn search(mut self, search: Search) -> Self {
+        self.search = search;
+        self
+    }
+
+    /// the number of times each setting is run, each run gets its own seed
+    pub fn repeats(mut self, repeats: usize) -> Self {
+        self.repeats = repeats.max(1);
+        self
+    }
+
+    /// the number of evenly spaced values grid search takes from a uniform range
+    pub fn steps(mut self, steps: usize) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    /// print each setting's results as they finish
+    pub fn debug(mut self, d: bool) -> Self {
+        self.debug_progress = d;
+        self
+    }
+
+    /// build the setting for a set of parameter values
+    pub fn setting(&self, values: &[f32]) -> Setting<E> {
+        let mut setting = self.base.clone();
+        for (param, value) in self.parameters.iter().zip(values.iter()) {
+            (param.set)(&mut setting, *value);
+        }
+        setting
+    }
+
+    /// Run the sweep. The runner is called for every run of every setting with the setting and the
+    /// seed for the run (0..repeats), and returns the trial result. Runs should hand the seed to the
+    /// population (Setting::evolve does), which seeds the random numbers given to the problem. Breeding
+    /// still draws from the thread's random number generator so runs with the same seed are independent samples.
+    /// Fails if a parameter's range can't be searched
+    pub fn run<F>(&self, runner: F) -> Result<SweepReport, &'static str>
+        where F: Fn(&Setting<E>, u64) -> Trial
+    {
+        if let Some(e) = self.invalid {
+            return Err(e);
+        }
+        let mut r = rand::thread_rng();
+        let mut report = SweepReport::default();
+        match &self.search {
+            Search::Grid => {
+                for values in self.grid() {
+                    report.rows.push(self.evaluate(&values, &runner));
+                }
+            },
+            Search::Random(samples) => {
+                for _ in 0..*samples {
+                    let values = self.parameters.iter().map(|x| x.range.sample(&mut r)).collect::<Vec<_>>();
+                    report.rows.push(self.evaluate(&values, &runner));
+                }
+            },
+            Search::Evolutionary { size, generations } => {
+                // a simple steady (mu + lambda) loop, the better half of the settings survive
+                // and each is copied and mutated to replace the worse half
+                let mut current = (0..(*size).max(2))
+                    .map(|_| {
+                        let values = self.parameters.iter().map(|x| x.range.sample(&mut r)).collect::<Vec<_>>();
+                        self.evaluate(&values, &runner)
+                    })
+                    .collect::<Vec<_>>();
+                report.rows.extend(current.iter().cloned());
+                for _ in 0..*generations {
+                    current.sort_by(|a, b| b.mean_best().partial_cmp(&a.mean_best()).unwrap());
+                    let keep = current.len().div_ceil(2);
+                    let children = (keep..current.len())
+                        .map(|i| {
+                            let parent = &current[i % keep];
+                            let values = self.parameters.iter()
+                                .zip(parent.values.iter())
+                                .map(|(param, (_, value))| param.range.mutate(&mut r, *value))
+                                .collect::<Vec<_>>();
+                            self.evaluate(&values, &runner)
+                        })
+                        .collect::<Vec<_>>();
+                    report.rows.extend(children.iter().cloned());
+                    current.truncate(keep);
+                    current.extend(children);
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// run every repeat of a setting
+    fn evaluate<F>(&self, values: &[f32], runner: &F) -> SweepRow
+        where F: Fn(&Setting<E>, u64) -> Trial
+    {
+        let setting = self.setting(values);
+        let row = SweepRow {
+            values: self.parameters.iter()
+                .zip(values.iter())
+                .map(|(param, value)| (param.name.clone(), *value))
+                .collect(),
+            trials: (0..self.repeats as u64).map(|seed| runner(&setting, seed)).collect()
+        };
+        if self.debug_progress {
+            print!("{}", SweepReport { rows: vec![row.clone()] });
+        }
+        row
+    }
+
+    /// every combination of the parameter values
+    fn grid(&self) -> Vec<Vec<f32>> {
+        self.parameters.iter().fold(vec![Vec::new()], |combos, param| {
+            combos.iter()
+                .flat_map(|combo| param.range.grid(self.steps).into_iter().map(move |value| {
+                    let mut next = combo.clone();
+                    next.push(value);
+                    next
+                }))
+                .collect()
+        })
+    }
+}
@@ -24,6 +24,12 @@ pub use models::{
         neat::Neat,
         neatenv::NeatEnvironment,
         activation::Activation,
+    },
+    genomes::{
+        bitstring::{BitString, BitStringEnvironment},
+        realvector::{RealVector, RealVectorEnvironment, RealMutation, RealCrossover},
+        permutation::{Permutation, PermutationEnvironment, PermutationCrossover, PermutationMutation},
+        integervector::{IntegerVector, IntegerVectorEnvironment, IntegerMutation}
     }
 };
 
@@ -31,13 +37,37 @@ pub use models::{
 pub use engine::{
     population::*,
     genome::Genome,
-    problem::Problem,
-    niche::Niche,
+    problem::{Problem, EvalContext},
+    niche::{Niche, NicheMember},
     generation::*,
     genocide::Genocide,
     environment::Envionment,
     survival::SurvivalCriteria,
-    survival::ParentalCriteria
+    survival::ParentalCriteria,
+    survival::Parent,
+    operator::{Operator, Pipeline},
+    genealogy::{Genealogy, Lineage, Ancestor, Improvement, MemberId},
+    alps::{Alps, AgingScheme},
+    behavior::Behavior,
+    mapelites::{MapElites, Archive, Elite, Tessellation},
+    stats::GenerationStats,
+    halloffame::{HallOfFame, Famer},
+    dataset::{MiniBatch, Scorer},
+    constraint::{Constraint, Constraints},
+    parsimony::Parsimony,
+    surrogate::{Surrogate, Model, FeatureFn},
+    distance::{DistanceCache, Sampling},
+    report::{RunReport, StopReason, SpeciesSummary},
+    logger::{RunLogger, RunLog, LogEntry, ChampionSummary},
+    sweep::{Sweep, Setting, Parameter, Range, Search, Trial, SweepRow, SweepReport},
+    experiment::{Experiment, ExperimentRun, ExperimentReport, RankSum},
+    sizing::{Sizing, Restart},
+    probes::{Probes, ProbeFn},
+    interactive::{Candidate, FillIn, Rated},
+    diagnostics::{Landscape, LandscapeReport, FitnessDistance, RandomWalk, Neutrality, diversity},
+    realvalued::RealValued,
+    cmaes::Cmaes,
+    differential::DifferentialEvolution
 };
 
 
@@ -0,0 +1,160 @@
+extern crate rand;
+
+use std::sync::{Arc, RwLock};
+use rand::Rng;
+
+use crate::engine::{
+    genome::Genome,
+    environment::{Envionment, one_fifth}
+};
+
+
+
+/// A fixed length string of bits, the classic genetic algorithm genome
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BitString {
+    pub bits: Vec<bool>
+}
+
+
+
+/// Settings for evolving bit strings
+///
+/// length: the number of bits in each bit string
+/// mutation_rate: the probability of flipping each bit during mutation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BitStringEnvironment {
+    pub length: usize,
+    pub mutation_rate: f32
+}
+
+
+
+impl BitString {
+
+    /// create a new bit string from a vec of bits
+    pub fn new(bits: Vec<bool>) -> Self {
+        BitString { bits }
+    }
+
+    /// create a random bit string of a given length
+    pub fn random(length: usize) -> Self {
+        let mut r = rand::thread_rng();
+        BitString { bits: (0..length).map(|_| r.gen::<bool>()).collect() }
+    }
+
+    /// number of bits set to true
+    pub fn ones(&self) -> usize {
+        self.bits.iter().filter(|x| **x).count()
+    }
+
+    /// uniform crossover - each bit is taken from either parent with equal probability
+    pub fn uniform(one: &BitString, two: &BitString) -> BitString {
+        let mut r = rand::thread_rng();
+        BitString {
+            bits: one.bits.iter()
+                .zip(two.bits.iter())
+                .map(|(a, b)| if r.gen::<bool>() { *a } else { *b })
+                .collect()
+        }
+    }
+
+    /// flip each bit with a given probability, if nothing was flipped then
+    /// flip one random bit so the mutation always changes something
+    pub fn flip(&mut self, rate: f32) {
+        let mut r = rand::thread_rng();
+        let mut flipped = false;
+        for bit in self.bits.iter_mut() {
+            if r.gen::<f32>() < rate {
+                *bit = !*bit;
+                flipped = true;
+            }
+        }
+        if !flipped && !self.bits.is_empty() {
+            let index = r.gen_range(0, self.bits.len());
+            self.bits[index] = !self.bits[index];
+        }
+    }
+}
+
+
+
+impl BitStringEnvironment {
+
+    pub fn new() -> Self {
+        BitStringEnvironment {
+            length: 0,
+            mutation_rate: 0.01
+        }
+    }
+
+
+    pub fn set_length(mut self, length: usize) -> Self {
+        self.length = length;
+        self
+    }
+
+
+    pub fn set_mutation_rate(mut self, rate: f32) -> Self {
+        self.mutation_rate = rate;
+        self
+    }
+}
+
+
+impl Default for BitStringEnvironment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+/// with adaptive species each species scales how often its bits flip
+impl Envionment for BitStringEnvironment {
+    fn adapt(&mut self, success_rate: f32) {
+        self.mutation_rate = (self.mutation_rate * one_fifth(success_rate)).clamp(1e-4, 0.5);
+    }
+}
+
+
+
+/// implement genome for a bit string
+impl Genome<BitString, BitStringEnvironment> for BitString {
+
+    /// either uniformly cross the two parents over, or flip bits in the more fit parent
+    fn crossover(one: &BitString, two: &BitString, env: Arc<RwLock<BitStringEnvironment>>, crossover_rate: f32) -> Option<BitString> {
+        if rand::thread_rng().gen::<f32>() < crossover_rate {
+            BitString::recombine(one, two, env)
+        } else {
+            BitString::mutate(one, env)
+        }
+    }
+
+
+    fn recombine(one: &BitString, two: &BitString, _: Arc<RwLock<BitStringEnvironment>>) -> Option<BitString> {
+        Some(BitString::uniform(one, two))
+    }
+
+
+    fn mutate(member: &BitString, env: Arc<RwLock<BitStringEnvironment>>) -> Option<BitString> {
+        let set = env.read().ok()?;
+        let mut child = member.clone();
+        child.flip(set.mutation_rate);
+        Some(child)
+    }
+
+
+    /// the hamming distance between the two bit strings as a fraction of their length
+    fn distance(one: &BitString, two: &BitString, _: Arc<RwLock<BitStringEnvironment>>) -> f32 {
+        let different = one.bits.iter()
+            .zip(two.bits.iter())
+            .filter(|(a, b)| a != b)
+            .count();
+        different as f32 / one.bits.len().max(1) as f32
+    }
+
+
+    fn base(env: &mut BitStringEnvironment) -> BitString {
+        BitString::random(env.length)
+    }
+}
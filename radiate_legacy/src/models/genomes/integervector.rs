@@ -0,0 +1,193 @@
+extern crate rand;
+
+use std::sync::{Arc, RwLock};
+use rand::Rng;
+
+use crate::engine::{
+    genome::Genome,
+    environment::{Envionment, one_fifth}
+};
+
+
+
+/// A fixed length vector of integers kept within an inclusive lower and upper bound
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntegerVector {
+    pub values: Vec<i32>
+}
+
+
+
+/// How an integer vector is mutated
+///
+/// Reset - replace the value with a new random value within the bounds
+/// Creep - add a random step between -size and size to the value
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum IntegerMutation {
+    Reset,
+    Creep(i32)
+}
+
+
+
+/// Settings for evolving integer vectors
+///
+/// length: the number of values in each vector
+/// bounds: the inclusive (lower, upper) bound of every value
+/// mutation_rate: the probability of mutating each value during mutation
+/// mutation: the mutation operator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegerVectorEnvironment {
+    pub length: usize,
+    pub bounds: (i32, i32),
+    pub mutation_rate: f32,
+    pub mutation: IntegerMutation
+}
+
+
+
+impl IntegerVector {
+
+    /// create a new integer vector from a vec of values
+    pub fn new(values: Vec<i32>) -> Self {
+        IntegerVector { values }
+    }
+
+    /// create a random integer vector of a given length within the bounds
+    pub fn random(length: usize, bounds: (i32, i32)) -> Self {
+        let mut r = rand::thread_rng();
+        IntegerVector { values: (0..length).map(|_| r.gen_range(bounds.0, bounds.1 + 1)).collect() }
+    }
+
+    /// uniform crossover - each value is taken from either parent with equal probability
+    pub fn uniform(one: &IntegerVector, two: &IntegerVector) -> IntegerVector {
+        let mut r = rand::thread_rng();
+        IntegerVector {
+            values: one.values.iter()
+                .zip(two.values.iter())
+                .map(|(a, b)| if r.gen::<bool>() { *a } else { *b })
+                .collect()
+        }
+    }
+
+    /// mutate each value with the given probability, if nothing was mutated then
+    /// mutate one random value so the mutation always changes something
+    pub fn mutate_with(&mut self, set: &IntegerVectorEnvironment) {
+        let mut r = rand::thread_rng();
+        let (lower, upper) = set.bounds;
+        let change = |value: &mut i32, r: &mut rand::rngs::ThreadRng| {
+            *value = match set.mutation {
+                IntegerMutation::Reset => r.gen_range(lower, upper + 1),
+                IntegerMutation::Creep(size) => (*value + r.gen_range(-size, size + 1)).max(lower).min(upper)
+            };
+        };
+        let mut mutated = false;
+        for value in self.values.iter_mut() {
+            if r.gen::<f32>() < set.mutation_rate {
+                change(value, &mut r);
+                mutated = true;
+            }
+        }
+        if !mutated && !self.values.is_empty() {
+            let index = r.gen_range(0, self.values.len());
+            change(&mut self.values[index], &mut r);
+        }
+    }
+}
+
+
+
+impl IntegerVectorEnvironment {
+
+    pub fn new() -> Self {
+        IntegerVectorEnvironment {
+            length: 0,
+            bounds: (0, 10),
+            mutation_rate: 0.1,
+            mutation: IntegerMutation::Reset
+        }
+    }
+
+
+    pub fn set_length(mut self, length: usize) -> Self {
+        self.length = length;
+        self
+    }
+
+
+    pub fn set_bounds(mut self, lower: i32, upper: i32) -> Self {
+        self.bounds = (lower, upper);
+        self
+    }
+
+
+    pub fn set_mutation_rate(mut self, rate: f32) -> Self {
+        self.mutation_rate = rate;
+        self
+    }
+
+
+    pub fn set_mutation(mut self, mutation: IntegerMutation) -> Self {
+        self.mutation = mutation;
+        self
+    }
+}
+
+
+impl Default for IntegerVectorEnvironment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+/// with adaptive species each species scales how often its values mutate
+impl Envionment for IntegerVectorEnvironment {
+    fn adapt(&mut self, success_rate: f32) {
+        self.mutation_rate = (self.mutation_rate * one_fifth(success_rate)).clamp(1e-4, 1.0);
+    }
+}
+
+
+
+/// implement genome for an integer vector
+impl Genome<IntegerVector, IntegerVectorEnvironment> for IntegerVector {
+
+    /// either uniformly cross the two parents over, or mutate the more fit parent
+    fn crossover(one: &IntegerVector, two: &IntegerVector, env: Arc<RwLock<IntegerVectorEnvironment>>, crossover_rate: f32) -> Option<IntegerVector> {
+        if rand::thread_rng().gen::<f32>() < crossover_rate {
+            IntegerVector::recombine(one, two, env)
+        } else {
+            IntegerVector::mutate(one, env)
+        }
+    }
+
+
+    fn recombine(one: &IntegerVector, two: &IntegerVector, _: Arc<RwLock<IntegerVectorEnvironment>>) -> Option<IntegerVector> {
+        Some(IntegerVector::uniform(one, two))
+    }
+
+
+    fn mutate(member: &IntegerVector, env: Arc<RwLock<IntegerVectorEnvironment>>) -> Option<IntegerVector> {
+        let set = env.read().ok()?;
+        let mut child = member.clone();
+        child.mutate_with(&set);
+        Some(child)
+    }
+
+
+    /// the manhattan distance between the two vectors relative to the width of the bounds
+    fn distance(one: &IntegerVector, two: &IntegerVector, env: Arc<RwLock<IntegerVectorEnvironment>>) -> f32 {
+        let width = env.read().map(|set| (set.bounds.1 - set.bounds.0).max(1)).unwrap_or(1) as f32;
+        let total = one.values.iter()
+            .zip(two.values.iter())
+            .map(|(a, b)| (a - b).abs() as f32 / width)
+            .sum::<f32>();
+        total / one.values.len().max(1) as f32
+    }
+
+
+    fn base(env: &mut IntegerVectorEnvironment) -> IntegerVector {
+        IntegerVector::random(env.length, env.bounds)
+    }
+}
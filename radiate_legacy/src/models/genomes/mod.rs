@@ -0,0 +1,4 @@
+pub mod bitstring;
+pub mod realvector;
+pub mod permutation;
+pub mod integervector;
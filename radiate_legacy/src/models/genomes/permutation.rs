@@ -0,0 +1,290 @@
+extern crate rand;
+
+use std::sync::{Arc, RwLock};
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+use crate::engine::{
+    genome::Genome,
+    environment::Envionment
+};
+
+
+
+/// An ordering of the numbers 0..n, used for problems like the traveling
+/// salesman or scheduling where the answer is the order things happen in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Permutation {
+    pub order: Vec<usize>
+}
+
+
+
+/// How two permutations are crossed over
+///
+/// Order - OX, keep a slice of the first parent and fill the rest in the order they appear in the second
+/// PartiallyMapped - PMX, keep a slice of the first parent and map the second parent's values around it
+/// Cycle - CX, alternate the cycles of positions between the two parents
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PermutationCrossover {
+    Order,
+    PartiallyMapped,
+    Cycle
+}
+
+
+
+/// How a permutation is mutated
+///
+/// Swap - swap two random positions
+/// Inversion - reverse a random slice
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PermutationMutation {
+    Swap,
+    Inversion
+}
+
+
+
+/// Settings for evolving permutations
+///
+/// length: the number of items in the permutation
+/// crossover: the crossover operator
+/// mutation: the mutation operator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermutationEnvironment {
+    pub length: usize,
+    pub crossover: PermutationCrossover,
+    pub mutation: PermutationMutation
+}
+
+
+
+impl Permutation {
+
+    /// create a new permutation from an ordering
+    pub fn new(order: Vec<usize>) -> Self {
+        Permutation { order }
+    }
+
+    /// create a random permutation of 0..length
+    pub fn random(length: usize) -> Self {
+        let mut order = (0..length).collect::<Vec<_>>();
+        order.shuffle(&mut rand::thread_rng());
+        Permutation { order }
+    }
+
+    /// pick a random slice (start, end) of the permutation, end is exclusive
+    fn random_slice(length: usize) -> (usize, usize) {
+        let mut r = rand::thread_rng();
+        let one = r.gen_range(0, length);
+        let two = r.gen_range(0, length);
+        (one.min(two), one.max(two) + 1)
+    }
+
+    /// order crossover (OX)
+    pub fn order_crossover(one: &Permutation, two: &Permutation) -> Permutation {
+        let length = one.order.len();
+        let (start, end) = Permutation::random_slice(length);
+        let mut child = vec![None; length];
+        let mut taken = vec![false; length];
+        for i in start..end {
+            child[i] = Some(one.order[i]);
+            taken[one.order[i]] = true;
+        }
+        // walk the second parent starting after the slice and fill the open spots in order
+        let mut position = end % length;
+        for offset in 0..length {
+            let value = two.order[(end + offset) % length];
+            if !taken[value] {
+                child[position] = Some(value);
+                taken[value] = true;
+                position = (position + 1) % length;
+            }
+        }
+        Permutation { order: child.into_iter().map(|x| x.unwrap()).collect() }
+    }
+
+    /// partially mapped crossover (PMX)
+    pub fn partially_mapped(one: &Permutation, two: &Permutation) -> Permutation {
+        let length = one.order.len();
+        let (start, end) = Permutation::random_slice(length);
+        let position_in_two = Permutation::positions(two);
+        let mut child = vec![None; length];
+        let mut taken = vec![false; length];
+        for i in start..end {
+            child[i] = Some(one.order[i]);
+            taken[one.order[i]] = true;
+        }
+        // place the values from the second parent's slice which didn't make it into the child
+        // by following the mapping between the parents until landing outside of the slice
+        for i in start..end {
+            let value = two.order[i];
+            if taken[value] {
+                continue;
+            }
+            let mut spot = i;
+            while spot >= start && spot < end {
+                spot = position_in_two[one.order[spot]];
+            }
+            child[spot] = Some(value);
+            taken[value] = true;
+        }
+        // everything else comes straight from the second parent
+        for (spot, value) in child.iter_mut().zip(two.order.iter()) {
+            if spot.is_none() {
+                *spot = Some(*value);
+            }
+        }
+        Permutation { order: child.into_iter().map(|x| x.unwrap()).collect() }
+    }
+
+    /// cycle crossover (CX)
+    pub fn cycle(one: &Permutation, two: &Permutation) -> Permutation {
+        let length = one.order.len();
+        let position_in_one = Permutation::positions(one);
+        let mut child = vec![None; length];
+        let mut from_one = true;
+        for start in 0..length {
+            if child[start].is_some() {
+                continue;
+            }
+            // follow the cycle starting at this position and take it all from one parent
+            let mut spot = start;
+            while child[spot].is_none() {
+                child[spot] = Some(if from_one { one.order[spot] } else { two.order[spot] });
+                spot = position_in_one[two.order[spot]];
+            }
+            from_one = !from_one;
+        }
+        Permutation { order: child.into_iter().map(|x| x.unwrap()).collect() }
+    }
+
+    /// swap two random positions
+    pub fn swap(&mut self) {
+        let mut r = rand::thread_rng();
+        let length = self.order.len();
+        if length > 1 {
+            self.order.swap(r.gen_range(0, length), r.gen_range(0, length));
+        }
+    }
+
+    /// reverse a random slice
+    pub fn inversion(&mut self) {
+        if !self.order.is_empty() {
+            let (start, end) = Permutation::random_slice(self.order.len());
+            self.order[start..end].reverse();
+        }
+    }
+
+    /// cross two permutations over using the operator from the environment
+    pub fn crossover_with(one: &Permutation, two: &Permutation, set: &PermutationEnvironment) -> Permutation {
+        match set.crossover {
+            PermutationCrossover::Order => Permutation::order_crossover(one, two),
+            PermutationCrossover::PartiallyMapped => Permutation::partially_mapped(one, two),
+            PermutationCrossover::Cycle => Permutation::cycle(one, two)
+        }
+    }
+
+    /// mutate the permutation using the operator from the environment
+    pub fn mutate_with(&mut self, set: &PermutationEnvironment) {
+        match set.mutation {
+            PermutationMutation::Swap => self.swap(),
+            PermutationMutation::Inversion => self.inversion()
+        }
+    }
+
+    /// a lookup from value to its position in the permutation
+    fn positions(perm: &Permutation) -> Vec<usize> {
+        let mut positions = vec![0; perm.order.len()];
+        for (index, value) in perm.order.iter().enumerate() {
+            positions[*value] = index;
+        }
+        positions
+    }
+}
+
+
+
+impl PermutationEnvironment {
+
+    pub fn new() -> Self {
+        PermutationEnvironment {
+            length: 0,
+            crossover: PermutationCrossover::Order,
+            mutation: PermutationMutation::Swap
+        }
+    }
+
+
+    pub fn set_length(mut self, length: usize) -> Self {
+        self.length = length;
+        self
+    }
+
+
+    pub fn set_crossover(mut self, crossover: PermutationCrossover) -> Self {
+        self.crossover = crossover;
+        self
+    }
+
+
+    pub fn set_mutation(mut self, mutation: PermutationMutation) -> Self {
+        self.mutation = mutation;
+        self
+    }
+}
+
+
+impl Default for PermutationEnvironment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+impl Envionment for PermutationEnvironment {}
+
+
+
+/// implement genome for a permutation
+impl Genome<Permutation, PermutationEnvironment> for Permutation {
+
+    /// either cross the two parents over, or mutate the more fit parent
+    fn crossover(one: &Permutation, two: &Permutation, env: Arc<RwLock<PermutationEnvironment>>, crossover_rate: f32) -> Option<Permutation> {
+        if rand::thread_rng().gen::<f32>() < crossover_rate {
+            Permutation::recombine(one, two, env)
+        } else {
+            Permutation::mutate(one, env)
+        }
+    }
+
+
+    fn recombine(one: &Permutation, two: &Permutation, env: Arc<RwLock<PermutationEnvironment>>) -> Option<Permutation> {
+        let set = env.read().ok()?;
+        Some(Permutation::crossover_with(one, two, &set))
+    }
+
+
+    fn mutate(member: &Permutation, env: Arc<RwLock<PermutationEnvironment>>) -> Option<Permutation> {
+        let set = env.read().ok()?;
+        let mut child = member.clone();
+        child.mutate_with(&set);
+        Some(child)
+    }
+
+
+    /// the fraction of positions which hold a different value
+    fn distance(one: &Permutation, two: &Permutation, _: Arc<RwLock<PermutationEnvironment>>) -> f32 {
+        let different = one.order.iter()
+            .zip(two.order.iter())
+            .filter(|(a, b)| a != b)
+            .count();
+        different as f32 / one.order.len().max(1) as f32
+    }
+
+
+    fn base(env: &mut PermutationEnvironment) -> Permutation {
+        Permutation::random(env.length)
+    }
+}
@@ -0,0 +1,301 @@
+extern crate rand;
+
+use std::sync::{Arc, RwLock};
+use rand::Rng;
+
+use crate::engine::{
+    genome::Genome,
+    environment::{Envionment, one_fifth},
+    realvalued::RealValued,
+    random
+};
+
+
+
+/// A fixed length vector of real numbers kept within a lower and upper bound
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RealVector {
+    pub values: Vec<f32>
+}
+
+
+
+/// How a real vector is mutated
+///
+/// Gaussian - add normally distributed noise with the given standard deviation
+///            scaled to the width of the bounds
+/// Polynomial - Deb's polynomial mutation with the given distribution index (eta),
+///              larger values keep children closer to their parent
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RealMutation {
+    Gaussian(f32),
+    Polynomial(f32)
+}
+
+
+
+/// How two real vectors are crossed over
+///
+/// Sbx - simulated binary crossover with the given distribution index (eta)
+/// Blend - BLX-alpha crossover, children are sampled uniformly from the range spanned
+///         by the parents extended by alpha times the distance between them on each side
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RealCrossover {
+    Sbx(f32),
+    Blend(f32)
+}
+
+
+
+/// Settings for evolving real vectors
+///
+/// length: the number of values in each vector
+/// bounds: the (lower, upper) bound of every value
+/// mutation_rate: the probability of mutating each value during mutation
+/// mutation: the mutation operator
+/// crossover: the crossover operator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RealVectorEnvironment {
+    pub length: usize,
+    pub bounds: (f32, f32),
+    pub mutation_rate: f32,
+    pub mutation: RealMutation,
+    pub crossover: RealCrossover
+}
+
+
+
+impl RealVector {
+
+    /// create a new real vector from a vec of values
+    pub fn new(values: Vec<f32>) -> Self {
+        RealVector { values }
+    }
+
+    /// create a random real vector of a given length uniformly within the bounds
+    pub fn random(length: usize, bounds: (f32, f32)) -> Self {
+        let mut r = rand::thread_rng();
+        RealVector { values: (0..length).map(|_| r.gen_range(bounds.0, bounds.1)).collect() }
+    }
+
+    /// simulated binary crossover, each value of the child is spread around the
+    /// parents' values the same way single point crossover spreads bits in a binary string
+    pub fn sbx(one: &RealVector, two: &RealVector, eta: f32, bounds: (f32, f32)) -> RealVector {
+        let mut r = rand::thread_rng();
+        RealVector {
+            values: one.values.iter()
+                .zip(two.values.iter())
+                .map(|(a, b)| {
+                    let u = r.gen::<f32>();
+                    let beta = if u <= 0.5 {
+                        (2.0 * u).powf(1.0 / (eta + 1.0))
+                    } else {
+                        (1.0 / (2.0 * (1.0 - u))).powf(1.0 / (eta + 1.0))
+                    };
+                    // pick either of the two children sbx produces
+                    let child = if r.gen::<bool>() {
+                        0.5 * ((1.0 + beta) * a + (1.0 - beta) * b)
+                    } else {
+                        0.5 * ((1.0 - beta) * a + (1.0 + beta) * b)
+                    };
+                    clamp(child, bounds)
+                })
+                .collect()
+        }
+    }
+
+    /// BLX-alpha crossover, sample each value uniformly from the extended range of the parents
+    pub fn blend(one: &RealVector, two: &RealVector, alpha: f32, bounds: (f32, f32)) -> RealVector {
+        let mut r = rand::thread_rng();
+        RealVector {
+            values: one.values.iter()
+                .zip(two.values.iter())
+                .map(|(a, b)| {
+                    let (low, high) = (a.min(*b), a.max(*b));
+                    let extend = alpha * (high - low);
+                    if high - low <= 0.0 && extend <= 0.0 {
+                        return *a;
+                    }
+                    clamp(r.gen_range(low - extend, high + extend), bounds)
+                })
+                .collect()
+        }
+    }
+
+    /// add gaussian noise to each value with a given probability, the standard
+    /// deviation is relative to the width of the bounds
+    pub fn gaussian(&mut self, rate: f32, std_dev: f32, bounds: (f32, f32)) {
+        let mut r = rand::thread_rng();
+        let width = bounds.1 - bounds.0;
+        for value in self.values.iter_mut() {
+            if r.gen::<f32>() < rate {
+                *value = clamp(random::normal(&mut r, *value, std_dev * width), bounds);
+            }
+        }
+    }
+
+    /// polynomial mutation of each value with a given probability
+    pub fn polynomial(&mut self, rate: f32, eta: f32, bounds: (f32, f32)) {
+        let mut r = rand::thread_rng();
+        let width = bounds.1 - bounds.0;
+        for value in self.values.iter_mut() {
+            if r.gen::<f32>() < rate {
+                let u = r.gen::<f32>();
+                let delta = if u < 0.5 {
+                    (2.0 * u).powf(1.0 / (eta + 1.0)) - 1.0
+                } else {
+                    1.0 - (2.0 * (1.0 - u)).powf(1.0 / (eta + 1.0))
+                };
+                *value = clamp(*value + delta * width, bounds);
+            }
+        }
+    }
+
+    /// cross two vectors over using the operator from the environment
+    pub fn crossover_with(one: &RealVector, two: &RealVector, set: &RealVectorEnvironment) -> RealVector {
+        match set.crossover {
+            RealCrossover::Sbx(eta) => RealVector::sbx(one, two, eta, set.bounds),
+            RealCrossover::Blend(alpha) => RealVector::blend(one, two, alpha, set.bounds)
+        }
+    }
+
+    /// mutate the vector using the operator from the environment
+    pub fn mutate_with(&mut self, set: &RealVectorEnvironment) {
+        match set.mutation {
+            RealMutation::Gaussian(std_dev) => self.gaussian(set.mutation_rate, std_dev, set.bounds),
+            RealMutation::Polynomial(eta) => self.polynomial(set.mutation_rate, eta, set.bounds)
+        }
+    }
+}
+
+
+
+/// keep a value within the bounds
+#[inline]
+fn clamp(value: f32, bounds: (f32, f32)) -> f32 {
+    value.max(bounds.0).min(bounds.1)
+}
+
+
+
+impl RealVectorEnvironment {
+
+    pub fn new() -> Self {
+        RealVectorEnvironment {
+            length: 0,
+            bounds: (-1.0, 1.0),
+            mutation_rate: 0.1,
+            mutation: RealMutation::Gaussian(0.1),
+            crossover: RealCrossover::Sbx(15.0)
+        }
+    }
+
+
+    pub fn set_length(mut self, length: usize) -> Self {
+        self.length = length;
+        self
+    }
+
+
+    pub fn set_bounds(mut self, lower: f32, upper: f32) -> Self {
+        self.bounds = (lower, upper);
+        self
+    }
+
+
+    pub fn set_mutation_rate(mut self, rate: f32) -> Self {
+        self.mutation_rate = rate;
+        self
+    }
+
+
+    pub fn set_mutation(mut self, mutation: RealMutation) -> Self {
+        self.mutation = mutation;
+        self
+    }
+
+
+    pub fn set_crossover(mut self, crossover: RealCrossover) -> Self {
+        self.crossover = crossover;
+        self
+    }
+}
+
+
+impl Default for RealVectorEnvironment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+/// with adaptive species each species scales the step size of its mutation
+impl Envionment for RealVectorEnvironment {
+    fn adapt(&mut self, success_rate: f32) {
+        let scale = one_fifth(success_rate);
+        self.mutation = match self.mutation {
+            RealMutation::Gaussian(sigma) => RealMutation::Gaussian((sigma * scale).clamp(1e-6, (self.bounds.1 - self.bounds.0).max(1e-6))),
+            // a bigger distribution index makes smaller steps
+            RealMutation::Polynomial(eta) => RealMutation::Polynomial((eta / scale).clamp(1.0, 1000.0))
+        };
+    }
+}
+
+
+
+/// a real vector can also be optimized directly by the continuous optimizers
+impl RealValued for RealVector {
+
+    fn from_values(values: Vec<f32>) -> Self {
+        RealVector { values }
+    }
+
+    fn values(&self) -> &[f32] {
+        &self.values
+    }
+}
+
+
+
+/// implement genome for a real vector
+impl Genome<RealVector, RealVectorEnvironment> for RealVector {
+
+    /// either cross the two parents over, or mutate the more fit parent
+    fn crossover(one: &RealVector, two: &RealVector, env: Arc<RwLock<RealVectorEnvironment>>, crossover_rate: f32) -> Option<RealVector> {
+        if rand::thread_rng().gen::<f32>() < crossover_rate {
+            RealVector::recombine(one, two, env)
+        } else {
+            RealVector::mutate(one, env)
+        }
+    }
+
+
+    fn recombine(one: &RealVector, two: &RealVector, env: Arc<RwLock<RealVectorEnvironment>>) -> Option<RealVector> {
+        let set = env.read().ok()?;
+        Some(RealVector::crossover_with(one, two, &set))
+    }
+
+
+    fn mutate(member: &RealVector, env: Arc<RwLock<RealVectorEnvironment>>) -> Option<RealVector> {
+        let set = env.read().ok()?;
+        let mut child = member.clone();
+        child.mutate_with(&set);
+        Some(child)
+    }
+
+
+    /// the euclidean distance between the two vectors relative to the width of the bounds
+    fn distance(one: &RealVector, two: &RealVector, env: Arc<RwLock<RealVectorEnvironment>>) -> f32 {
+        let width = env.read().map(|set| set.bounds.1 - set.bounds.0).unwrap_or(1.0);
+        let total = one.values.iter()
+            .zip(two.values.iter())
+            .map(|(a, b)| ((a - b) / width).powi(2))
+            .sum::<f32>();
+        total.sqrt()
+    }
+
+
+    fn base(env: &mut RealVectorEnvironment) -> RealVector {
+        RealVector::random(env.length, env.bounds)
+    }
+}
@@ -1 +1,2 @@
-pub mod neat;
\ No newline at end of file
+pub mod neat;
+pub mod genomes;
\ No newline at end of file
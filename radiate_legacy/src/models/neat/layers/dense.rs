@@ -730,49 +730,66 @@ impl Layer for Dense {
 impl Genome<Dense, NeatEnvironment> for Dense
     where Dense: Layer
 {
+    /// flip a coin on the crossover rate to decide if the child is crossed over or mutated
     fn crossover(child: &Dense, parent_two: &Dense, env: Arc<RwLock<NeatEnvironment>>, crossover_rate: f32) -> Option<Dense> {
+        if rand::thread_rng().gen::<f32>() < crossover_rate {
+            Dense::recombine(child, parent_two, env)
+        } else {
+            Dense::mutate(child, env)
+        }
+    }
+
+
+
+    fn recombine(child: &Dense, parent_two: &Dense, env: Arc<RwLock<NeatEnvironment>>) -> Option<Dense> {
         let mut new_child = child.clone();
         let set = (*env).read().ok()?;
         let mut r = rand::thread_rng();
-        if r.gen::<f32>() < crossover_rate {
-            for edge in new_child.edges.iter_mut() {
-                // if the edge is in both networks, then randomly assign the weight to the edge
-                // because we are already looping over the most fit parent, we only need to change the 
-                // weight to the second parent if necessary.
-                if let Some(parent_edge) = parent_two.get_edge_by_innov(&edge.innov) {
-                    if r.gen::<f32>() < 0.5 {
-                        edge.update_weight(parent_edge.weight, &mut new_child.nodes);
-                    }
+        for edge in new_child.edges.iter_mut() {
+            // if the edge is in both networks, then randomly assign the weight to the edge
+            // because we are already looping over the most fit parent, we only need to change the 
+            // weight to the second parent if necessary.
+            if let Some(parent_edge) = parent_two.get_edge_by_innov(&edge.innov) {
+                if r.gen::<f32>() < 0.5 {
+                    edge.update_weight(parent_edge.weight, &mut new_child.nodes);
+                }
 
-                    // if the edge is deactivated in either network and a random number is less than the 
-                    // reactivate parameter, then reactivate the edge and insert it back into the network
-                    if (!edge.active || !parent_edge.active) && r.gen::<f32>() < set.reactivate? {
-                        edge.enable(&mut new_child.nodes);
-                    }
+                // if the edge is deactivated in either network and a random number is less than the 
+                // reactivate parameter, then reactivate the edge and insert it back into the network
+                if (!edge.active || !parent_edge.active) && r.gen::<f32>() < set.reactivate? {
+                    edge.enable(&mut new_child.nodes);
                 }
             }
-        } else {
-            // if a random number is less than the edit_weights parameter, then edit the weights of the network edges
-            // add a possible new node to the network randomly 
-            // attempt to add a new edge to the network, there is a chance this operation will add no edge
-            if r.gen::<f32>() < set.weight_mutate_rate? {
-                new_child.edit_weights(set.edit_weights?, set.weight_perturb?);
-            }
+        }
+        Some(new_child)
+    }
 
-            // if the layer is a dense pool then it can add nodes and connections to the layer as well
-            if new_child.layer_type == LayerType::DensePool {
-                if r.gen::<f32>() < set.new_node_rate? {
-                    let act_func = *set.activation_functions.choose(&mut r)?;
-                    if r.gen::<f32>() < set.recurrent_neuron_rate? {
-                        new_child.add_node(act_func, NeuronDirection::Recurrent);
-                    } else {
-                        new_child.add_node(act_func, NeuronDirection::Forward);
-                    }
-                }
-                if r.gen::<f32>() < set.new_edge_rate? {
-                    new_child.add_edge();
+
+
+    fn mutate(child: &Dense, env: Arc<RwLock<NeatEnvironment>>) -> Option<Dense> {
+        let mut new_child = child.clone();
+        let set = (*env).read().ok()?;
+        let mut r = rand::thread_rng();
+        // if a random number is less than the edit_weights parameter, then edit the weights of the network edges
+        // add a possible new node to the network randomly 
+        // attempt to add a new edge to the network, there is a chance this operation will add no edge
+        if r.gen::<f32>() < set.weight_mutate_rate? {
+            new_child.edit_weights(set.edit_weights?, set.weight_perturb?);
+        }
+
+        // if the layer is a dense pool then it can add nodes and connections to the layer as well
+        if new_child.layer_type == LayerType::DensePool {
+            if r.gen::<f32>() < set.new_node_rate? {
+                let act_func = *set.activation_functions.choose(&mut r)?;
+                if r.gen::<f32>() < set.recurrent_neuron_rate? {
+                    new_child.add_node(act_func, NeuronDirection::Recurrent);
+                } else {
+                    new_child.add_node(act_func, NeuronDirection::Forward);
                 }
             }
+            if r.gen::<f32>() < set.new_edge_rate? {
+                new_child.add_edge();
+            }
         }
         Some(new_child)
     }
@@ -790,6 +807,13 @@ impl Genome<Dense, NeatEnvironment> for Dense
         let two_score = similar / two.edges.len() as f32;
         2.0 - (one_score + two_score)
     }
+
+
+
+    /// the size of the layer is its nodes plus its active edges, disabled edges don't do anything
+    fn complexity(member: &Dense) -> f32 {
+        (member.nodes.len() + member.edges.iter().filter(|x| x.active).count()) as f32
+    }
 }
 
 
@@ -192,6 +192,12 @@ impl Genome<GRU, NeatEnvironment> for GRU
         result += Dense::distance(&one.e_gate, &two.e_gate, Arc::clone(&env));
         result
     }
+
+
+    /// the size of a GRU layer is the size of its gates
+    fn complexity(member: &GRU) -> f32 {
+        Dense::complexity(&member.f_gate) + Dense::complexity(&member.o_gate) + Dense::complexity(&member.e_gate)
+    }
 }
 
 /// implement display for the GRU layer of the network
@@ -443,6 +443,15 @@ impl Genome<LSTM, NeatEnvironment> for LSTM
         result += Dense::distance(&one.v_gate.read().unwrap(), &two.v_gate.read().unwrap(), Arc::clone(&env));
         result
     }
+
+
+    /// the size of an LSTM layer is the size of its gates
+    fn complexity(member: &LSTM) -> f32 {
+        [&member.g_gate, &member.i_gate, &member.f_gate, &member.o_gate, &member.v_gate]
+            .iter()
+            .map(|gate| Dense::complexity(&gate.read().unwrap()))
+            .sum()
+    }
 }
 
 /// implement display for the LSTM layer of the network
@@ -359,4 +359,17 @@ impl Genome<Neat, NeatEnvironment> for Neat {
         total_distance
     }
 
+
+    /// the size of the network is the total size of its layers - nodes plus active edges
+    fn complexity(member: &Neat) -> f32 {
+        member.layers
+            .iter()
+            .map(|layer| match layer.layer_type {
+                LayerType::Dense | LayerType::DensePool => Dense::complexity(layer.as_ref()),
+                LayerType::LSTM => LSTM::complexity(layer.as_ref()),
+                LayerType::GRU => GRU::complexity(layer.as_ref())
+            })
+            .sum()
+    }
+
 }
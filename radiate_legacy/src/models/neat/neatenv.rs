@@ -1,7 +1,7 @@
 
 use super::activation::Activation;
 
-use crate::engine::environment::Envionment;
+use crate::engine::environment::{Envionment, one_fifth};
 
 
 /// Configuration settings for the NeatAlgorithm
@@ -118,4 +118,11 @@ impl Default for NeatEnvironment {
 
 
 
-impl Envionment for NeatEnvironment {}
+/// with adaptive species each species scales how far and how often its weights are perturbed
+impl Envionment for NeatEnvironment {
+    fn adapt(&mut self, success_rate: f32) {
+        let scale = one_fifth(success_rate);
+        self.weight_perturb = self.weight_perturb.map(|x| (x * scale).clamp(1e-3, 10.0));
+        self.weight_mutate_rate = self.weight_mutate_rate.map(|x| (x * scale).clamp(1e-3, 1.0));
+    }
+}
@@ -0,0 +1,95 @@
+extern crate radiate_legacy;
+
+use std::sync::{Arc, RwLock};
+use radiate_legacy::prelude::*;
+
+
+
+/// the negative sphere function
+pub struct Sphere;
+
+impl Problem<RealVector> for Sphere {
+
+    fn empty() -> Self { Sphere }
+
+    fn solve(&self, member: &mut RealVector) -> f32 {
+        -member.values.iter().map(|x| x.powi(2)).sum::<f32>()
+    }
+}
+
+
+
+#[test]
+fn environments_follow_the_one_fifth_rule() {
+    let mut env = BitStringEnvironment::new().set_mutation_rate(0.1);
+    env.adapt(0.5);
+    assert!(env.mutation_rate > 0.1);
+    env.adapt(0.0);
+    env.adapt(0.0);
+    assert!(env.mutation_rate < 0.1);
+    let mut env = RealVectorEnvironment::new().set_mutation(RealMutation::Gaussian(0.5));
+    env.adapt(0.0);
+    assert_eq!(env.mutation, RealMutation::Gaussian(0.5 * 0.85));
+    let mut env = NeatEnvironment::new().set_weight_perturb(1.0);
+    env.adapt(1.0);
+    assert!(env.weight_perturb.unwrap() > 1.0);
+    assert_eq!(env.weight_mutate_rate, None);
+}
+
+
+
+#[test]
+fn species_adapt_their_own_environment() {
+    // two species bred children from the same global settings, all of the first one's children beat
+    // their parents and none of the second one's did, so their step sizes move in opposite directions
+    let global = RealVectorEnvironment::new().set_length(3).set_mutation(RealMutation::Gaussian(0.5));
+    let mut gen = Generation::<RealVector, RealVectorEnvironment>::new();
+    let member = RealVector::base(&mut global.clone());
+    gen.species = (0..2)
+        .map(|_| {
+            let mut species = Niche::new(&member, 0.0, 0);
+            species.environment = Some(Arc::new(RwLock::new(global.clone())));
+            species
+        })
+        .collect();
+    gen.members = (0..10)
+        .map(|i| {
+            let mut child = Container::new(member.clone(), Lineage::new(1, Vec::new(), Vec::new()));
+            child.bred_by = Some(gen.species[i % 2].niche_id);
+            child.objective = if i % 2 == 0 { 1.0 } else { -1.0 };
+            child
+        })
+        .collect();
+    gen.adapt_species();
+    let sigmas = gen.species.iter()
+        .map(|spec| spec.environment.as_ref().unwrap().read().unwrap().mutation)
+        .collect::<Vec<_>>();
+    assert_eq!(sigmas, vec![RealMutation::Gaussian(0.5 / 0.85), RealMutation::Gaussian(0.5 * 0.85)]);
+}
+
+
+
+#[test]
+fn adaptive_species_evolve() {
+    let mut population = Population::<RealVector, RealVectorEnvironment, Sphere>::new()
+        .size(100)
+        .constrain(RealVectorEnvironment::new()
+            .set_length(3)
+            .set_mutation(RealMutation::Gaussian(0.5)))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.2,
+            distance: 0.5,
+            species_target: 5
+        })
+        .dynamic_distance(true)
+        .adaptive_species(true);
+    let report = population.run(|_, _, num| num == 30).unwrap();
+    let curve = report.fitness_curve();
+    assert!(curve[curve.len() - 1] > curve[0]);
+    // every species holds its own environment and they have moved away from the global one
+    let environments = population.species_environments();
+    assert!(!environments.is_empty());
+    assert!(environments.iter().any(|env| env.read().unwrap().mutation != RealMutation::Gaussian(0.5)));
+}
@@ -0,0 +1,69 @@
+extern crate radiate_legacy;
+
+use radiate_legacy::prelude::*;
+
+
+
+/// count the ones in the bit string
+pub struct OneMax;
+
+impl Problem<BitString> for OneMax {
+
+    fn empty() -> Self { OneMax }
+
+    fn solve(&self, member: &mut BitString) -> f32 {
+        member.ones() as f32
+    }
+}
+
+
+
+#[test]
+fn alps_max_ages() {
+    let alps = Alps::new().layers(5).age_gap(5);
+    let ages = (0..5).map(|x| alps.max_age(x)).collect::<Vec<_>>();
+    assert_eq!(ages, vec![Some(5), Some(10), Some(20), Some(45), None]);
+    let alps = alps.aging(AgingScheme::Fibonacci);
+    let ages = (0..5).map(|x| alps.max_age(x)).collect::<Vec<_>>();
+    assert_eq!(ages, vec![Some(5), Some(10), Some(15), Some(25), None]);
+}
+
+
+
+#[test]
+fn alps_onemax() {
+    let alps = Alps::new()
+        .layers(4)
+        .age_gap(5)
+        .aging(AgingScheme::Linear);
+    let mut population = Population::<BitString, BitStringEnvironment, OneMax>::new()
+        .size(30)
+        .constrain(BitStringEnvironment::new()
+            .set_length(16)
+            .set_mutation_rate(0.05))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.5,
+            distance: 0.5,
+            species_target: 5
+        })
+        .alps(alps.clone());
+    let mut best = 0.0;
+    for _ in 0..300 {
+        let (fit, _) = population.train().unwrap();
+        best = fit;
+        if fit == 16.0 {
+            break;
+        }
+    }
+    assert_eq!(best, 16.0);
+    // nobody is kept in a layer they are too old for
+    let layers = population.layers();
+    assert_eq!(layers.len(), 4);
+    for (index, layer) in layers.iter().enumerate() {
+        let max_age = alps.max_age(index).unwrap_or(usize::MAX);
+        // members age by one when they survive, they move up at the start of the next generation
+        assert!(layer.members.iter().all(|x| x.age <= max_age.saturating_add(1)));
+    }
+}
@@ -0,0 +1,180 @@
+extern crate radiate_legacy;
+
+use radiate_legacy::prelude::*;
+
+
+
+/// one max where any member with more than 8 ones is infeasible
+pub struct CappedOneMax;
+
+impl Problem<BitString> for CappedOneMax {
+
+    fn empty() -> Self { CappedOneMax }
+
+    fn solve(&self, member: &mut BitString) -> f32 {
+        member.ones() as f32
+    }
+
+    fn constraint_violation(&self, member: &BitString) -> f32 {
+        (member.ones() as f32 - 8.0).max(0.0)
+    }
+}
+
+
+
+fn population(handling: Constraint) -> Population<BitString, BitStringEnvironment, CappedOneMax> {
+    Population::<BitString, BitStringEnvironment, CappedOneMax>::new()
+        .size(50)
+        .constrain(BitStringEnvironment::new()
+            .set_length(16)
+            .set_mutation_rate(0.05))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.5,
+            distance: 0.5,
+            species_target: 5
+        })
+        .constraints(handling)
+}
+
+
+
+#[test]
+fn feasibility_rules() {
+    let top = population(Constraint::FeasibilityRules)
+        .run(|_, fit, num| fit == 8.0 || num == 300)
+        .unwrap().champion;
+    assert_eq!(top.ones(), 8);
+}
+
+
+
+#[test]
+fn adaptive_penalty() {
+    let top = population(Constraint::adaptive_penalty())
+        .run(|_, fit, num| fit == 8.0 || num == 300)
+        .unwrap().champion;
+    assert_eq!(top.ones(), 8);
+}
+
+
+
+#[test]
+fn stochastic_ranking() {
+    let top = population(Constraint::StochasticRanking(0.45))
+        .run(|_, fit, num| fit == 8.0 || num == 300)
+        .unwrap().champion;
+    assert_eq!(top.ones(), 8);
+}
+
+
+
+#[test]
+fn infeasible_members_rank_below_feasible() {
+    let problem = CappedOneMax;
+    let mut members = (0..=16)
+        .map(|ones| {
+            let mut member = BitString { bits: (0..16).map(|i| i < ones).collect() };
+            let mut cont = Container::new(member.clone(), Lineage::new(0, Vec::new(), Vec::new()));
+            cont.fitness_score = problem.solve(&mut member);
+            cont.violation = problem.constraint_violation(&member);
+            cont
+        })
+        .collect::<Vec<Container<BitString, BitStringEnvironment>>>();
+    Constraints::new(Constraint::FeasibilityRules).apply(&mut members);
+    for cont in members.iter() {
+        assert_eq!(cont.objective, cont.member.ones() as f32);
+        if cont.is_feasible() {
+            assert_eq!(cont.fitness_score, cont.objective);
+        } else {
+            // the worst feasible member has no ones, so infeasible members score below zero by their violation
+            assert_eq!(cont.fitness_score, -cont.violation);
+        }
+    }
+    let best = members.iter()
+        .max_by(|a, b| a.fitness_score.partial_cmp(&b.fitness_score).unwrap())
+        .unwrap();
+    assert_eq!(best.objective, 8.0);
+}
+
+
+
+#[test]
+fn survivor_criteria_is_used() {
+    let mut population = population(Constraint::FeasibilityRules)
+        .survivor_criteria(SurvivalCriteria::TopNumber(10));
+    population.train().unwrap();
+    let survivors = population.members_mut().iter()
+        .filter(|x| x.lineage.birth == 0)
+        .count();
+    assert_eq!(survivors, 10);
+}
+
+
+
+#[test]
+fn rechecked_champion_is_feasible() {
+    // without a penalty the members with the most ones rank on top, but they are infeasible
+    let mut population = population(Constraint::AdaptivePenalty { penalty: 0.0, increase: 2.0, decrease: 0.5, window: 1 })
+        .recheck(50);
+    for _ in 0..10 {
+        let (fit, top) = population.train().unwrap();
+        assert_eq!(fit, top.ones() as f32);
+        // once nothing is feasible the champion falls back to the member which violates the least
+        if population.stats().unwrap().feasible > 0 {
+            assert!(top.ones() <= 8);
+        }
+    }
+}
+
+
+
+/// every member breaks the constraints
+pub struct Impossible;
+
+impl Problem<BitString> for Impossible {
+
+    fn empty() -> Self { Impossible }
+
+    fn solve(&self, member: &mut BitString) -> f32 {
+        member.ones() as f32
+    }
+
+    fn constraint_violation(&self, _: &BitString) -> f32 {
+        1.0
+    }
+}
+
+
+
+#[test]
+fn reports_only_count_feasible_members() {
+    let mut population = population(Constraint::FeasibilityRules).hall_of_fame(10);
+    for _ in 0..10 {
+        population.train().unwrap();
+    }
+    assert_eq!(population.get_hall_of_fame().len(), 10);
+    assert!(population.get_hall_of_fame().members.iter().all(|x| x.member.ones() <= 8 && x.fitness <= 8.0));
+    assert!(population.history().iter().all(|x| x.feasible > 0 && x.best <= 8.0));
+}
+
+
+
+#[test]
+fn adaptive_penalty_adapts_once_a_generation_with_age_layers() {
+    let mut population = Population::<BitString, BitStringEnvironment, Impossible>::new()
+        .size(20)
+        .constrain(BitStringEnvironment::new().set_length(16))
+        .populate_base()
+        .alps(Alps::new().layers(3).age_gap(2))
+        .constraints(Constraint::AdaptivePenalty { penalty: 1.0, increase: 2.0, decrease: 0.5, window: 1 });
+    for _ in 0..5 {
+        population.train().unwrap();
+    }
+    // the best member is infeasible every generation so the penalty doubles once a generation
+    assert_eq!(population.get_constraints().unwrap().penalty, 32.0);
+    // nothing is feasible, so nothing makes it into the hall of fame and the stats fall back to everyone
+    assert!(population.get_hall_of_fame().is_empty());
+    assert!(population.history().iter().all(|x| x.feasible == 0 && x.size > 0));
+}
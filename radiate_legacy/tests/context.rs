@@ -0,0 +1,99 @@
+extern crate radiate_legacy;
+extern crate rand;
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::Ordering;
+use rand::Rng;
+use radiate_legacy::prelude::*;
+
+
+
+/// A noisy one max which records the noise it gave each member, and
+/// cancels the run once it reaches a given generation
+pub struct NoisyOneMax {
+    pub seen: Arc<Mutex<Vec<(usize, usize, f32)>>>,
+    pub cancel_at: usize
+}
+
+impl Problem<BitString> for NoisyOneMax {
+
+    fn empty() -> Self {
+        NoisyOneMax { seen: Arc::new(Mutex::new(Vec::new())), cancel_at: usize::MAX }
+    }
+
+    fn solve(&self, member: &mut BitString) -> f32 {
+        member.ones() as f32
+    }
+
+    fn solve_with_context(&self, member: &mut BitString, context: &mut EvalContext) -> f32 {
+        let noise = context.rng.gen::<f32>();
+        self.seen.lock().unwrap().push((context.generation, context.index, noise));
+        if context.generation >= self.cancel_at {
+            context.cancel.store(true, Ordering::Relaxed);
+        }
+        self.solve(member) + noise
+    }
+}
+
+
+
+fn noise(seed: u64) -> Vec<(usize, usize, f32)> {
+    let problem = NoisyOneMax::empty();
+    let seen = Arc::clone(&problem.seen);
+    let mut population = Population::<BitString, BitStringEnvironment, NoisyOneMax>::new()
+        .size(20)
+        .constrain(BitStringEnvironment::new().set_length(16))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.5,
+            distance: 0.5,
+            species_target: 5
+        })
+        .impose(problem)
+        .seed(seed);
+    for _ in 0..3 {
+        population.train().unwrap();
+    }
+    let mut seen = seen.lock().unwrap().clone();
+    seen.sort_by_key(|x| (x.0, x.1));
+    seen
+}
+
+
+
+#[test]
+fn seeded_context_is_reproducible() {
+    let one = noise(7);
+    assert_eq!(one.len(), 60);
+    assert_eq!(one, noise(7));
+    assert_ne!(one, noise(8));
+    // every generation is seen, each with its own members
+    assert_eq!(one.iter().filter(|x| x.0 == 2).count(), 20);
+}
+
+
+
+#[test]
+fn problem_can_cancel_run() {
+    let problem = NoisyOneMax {
+        seen: Arc::new(Mutex::new(Vec::new())),
+        cancel_at: 3
+    };
+    let mut population = Population::<BitString, BitStringEnvironment, NoisyOneMax>::new()
+        .size(20)
+        .constrain(BitStringEnvironment::new().set_length(16))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.5,
+            distance: 0.5,
+            species_target: 5
+        })
+        .impose(problem);
+    let cancel = population.cancel_handle();
+    let report = population.run(|_, _, num| num == 100).unwrap();
+    assert_eq!(report.stop, StopReason::Cancelled);
+    assert_eq!(report.generations(), 4);
+    assert!(cancel.load(Ordering::Relaxed));
+}
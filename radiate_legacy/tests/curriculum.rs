@@ -0,0 +1,82 @@
+extern crate radiate_legacy;
+
+use radiate_legacy::prelude::*;
+
+
+
+/// one max over only the first `window` bits, the window grows once the population solves it
+pub struct GrowingOneMax {
+    pub window: usize,
+    pub changes: usize
+}
+
+impl Problem<BitString> for GrowingOneMax {
+
+    fn empty() -> Self { GrowingOneMax { window: 4, changes: 0 } }
+
+    fn solve(&self, member: &mut BitString) -> f32 {
+        member.bits.iter().take(self.window).filter(|x| **x).count() as f32
+    }
+
+    fn advance(&mut self, stats: &GenerationStats) -> bool {
+        if stats.best >= self.window as f32 && self.window < 16 {
+            self.window += 4;
+            self.changes += 1;
+            return true;
+        }
+        false
+    }
+}
+
+
+
+fn population() -> Population<BitString, BitStringEnvironment, GrowingOneMax> {
+    Population::<BitString, BitStringEnvironment, GrowingOneMax>::new()
+        .size(50)
+        .constrain(BitStringEnvironment::new()
+            .set_length(16)
+            .set_mutation_rate(0.05))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.5,
+            distance: 0.5,
+            species_target: 5
+        })
+        .hall_of_fame(5)
+}
+
+
+
+#[test]
+fn problem_advances_and_hall_of_fame_is_rescored() {
+    let mut population = population();
+    let top = population.run(|_, fit, num| fit == 16.0 || num == 500).unwrap().champion;
+    assert_eq!(top.ones(), 16);
+    let problem = population.problem();
+    let problem = problem.read().unwrap();
+    assert_eq!(problem.window, 16);
+    assert_eq!(problem.changes, 3);
+    // the hall of fame is scored against the final problem
+    let hall_of_fame = population.get_hall_of_fame();
+    assert_eq!(hall_of_fame.len(), 5);
+    for famer in hall_of_fame.members.iter() {
+        assert_eq!(famer.fitness, problem.solve(&mut famer.member.clone()));
+    }
+    assert_eq!(population.stats().unwrap().best, 16.0);
+}
+
+
+
+#[test]
+fn problem_can_be_swapped() {
+    let mut population = population();
+    for _ in 0..5 {
+        population.train().unwrap();
+    }
+    population.swap_problem(GrowingOneMax { window: 16, changes: 0 });
+    let hall_of_fame = population.get_hall_of_fame();
+    for famer in hall_of_fame.members.iter() {
+        assert_eq!(famer.fitness, famer.member.ones() as f32);
+    }
+}
@@ -0,0 +1,103 @@
+extern crate radiate_legacy;
+extern crate rand;
+
+use rand::Rng;
+use radiate_legacy::prelude::*;
+
+
+
+/// the negative squared error of a linear model whose weights are the values of the vector
+#[derive(Default, Clone)]
+pub struct LinearError;
+
+impl Scorer<RealVector> for LinearError {
+
+    fn score(&self, member: &mut RealVector, input: &[f32], answer: &[f32]) -> f32 {
+        let guess = member.values.iter()
+            .zip(input.iter().chain(Some(&1.0)))
+            .map(|(w, x)| w * x)
+            .sum::<f32>();
+        -(guess - answer[0]).powi(2)
+    }
+}
+
+
+
+fn dataset(rows: usize) -> MiniBatch<RealVector, LinearError> {
+    let mut r = rand::thread_rng();
+    let inputs = (0..rows)
+        .map(|_| vec![r.gen_range(-1.0, 1.0), r.gen_range(-1.0, 1.0)])
+        .collect::<Vec<Vec<f32>>>();
+    let answers = inputs.iter()
+        .map(|x| vec![0.3 * x[0] - 0.5 * x[1] + 0.2])
+        .collect();
+    MiniBatch::new(inputs, answers, 32)
+}
+
+
+
+#[test]
+fn batches_are_resampled() {
+    let mut problem = dataset(1000).seed(3);
+    let first = problem.batch().to_vec();
+    assert_eq!(first.len(), 32);
+    assert_eq!(dataset(1000).seed(3).batch().len(), 32);
+    // a new batch is the same problem, so it doesn't count as a change
+    assert!(!problem.advance(&GenerationStats::default()));
+    assert_ne!(problem.batch(), &first[..]);
+    // a batch as big as the dataset is the whole dataset and never changes
+    let mut full = MiniBatch::<RealVector, LinearError>::new(vec![vec![0.0, 0.0]; 10], vec![vec![0.0]; 10], 50);
+    assert!(full.is_full());
+    assert!(!full.advance(&GenerationStats::default()));
+}
+
+
+
+#[test]
+fn mini_batch_regression() {
+    let problem = dataset(5000);
+    let check = problem.clone();
+    let top = Population::<RealVector, RealVectorEnvironment, MiniBatch<RealVector, LinearError>>::new()
+        .size(100)
+        .constrain(RealVectorEnvironment::new()
+            .set_length(3)
+            .set_mutation(RealMutation::Polynomial(20.0)))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.5,
+            distance: 0.5,
+            species_target: 5
+        })
+        .impose(problem)
+        .recheck(5)
+        .run(|_, fit, num| fit > -1e-3 || num == 300)
+        .unwrap().champion;
+    assert!(check.solve_full(&mut top.clone()) > -1e-3, "did not fit the data: {:?}", top);
+}
+
+
+
+#[test]
+fn resampling_is_not_a_new_problem() {
+    let problem = dataset(1000);
+    let check = problem.clone();
+    let mut population = Population::<RealVector, RealVectorEnvironment, MiniBatch<RealVector, LinearError>>::new()
+        .size(50)
+        .constrain(RealVectorEnvironment::new().set_length(3))
+        .populate_base()
+        .impose(problem)
+        .hall_of_fame(5)
+        .surrogate(Surrogate::new(|member: &RealVector| member.values.clone()).warmup(usize::MAX));
+    for _ in 0..3 {
+        population.train().unwrap();
+    }
+    // the surrogate would forget everything it learned every generation if each batch was a new problem
+    assert_eq!(population.get_surrogate().unwrap().len(), 150);
+    // when the problem does change the hall of fame is scored on the whole dataset
+    population.swap_problem(check.clone());
+    assert_eq!(population.get_surrogate().unwrap().len(), 0);
+    for famer in population.get_hall_of_fame().members.iter() {
+        assert_eq!(famer.fitness, check.solve_full(&mut famer.member.clone()));
+    }
+}
@@ -0,0 +1,140 @@
+extern crate radiate_legacy;
+extern crate serde_json;
+
+use std::sync::{Arc, RwLock};
+use radiate_legacy::prelude::*;
+
+
+
+/// the more ones the better
+pub struct OneMax;
+
+impl Problem<BitString> for OneMax {
+
+    fn empty() -> Self { OneMax }
+
+    fn solve(&self, member: &mut BitString) -> f32 {
+        member.ones() as f32
+    }
+}
+
+
+
+/// the more zeros the better, except all ones is best of all
+pub struct Trap;
+
+impl Problem<BitString> for Trap {
+
+    fn empty() -> Self { Trap }
+
+    fn solve(&self, member: &mut BitString) -> f32 {
+        let length = member.bits.len();
+        match member.ones() {
+            ones if ones == length => length as f32 + 1.0,
+            ones => (length - ones) as f32
+        }
+    }
+}
+
+
+
+/// every member is as good as the next
+pub struct Flat;
+
+impl Problem<BitString> for Flat {
+
+    fn empty() -> Self { Flat }
+
+    fn solve(&self, _: &mut BitString) -> f32 {
+        1.0
+    }
+}
+
+
+
+fn environment() -> Arc<RwLock<BitStringEnvironment>> {
+    Arc::new(RwLock::new(BitStringEnvironment::new().set_length(50).set_mutation_rate(0.02)))
+}
+
+
+
+#[test]
+fn fitness_distance_correlation_tells_easy_from_deceptive() {
+    let env = environment();
+    let optimum = BitString::new(vec![true; 50]);
+    let landscape = Landscape::new().samples(300);
+    let easy = landscape.fitness_distance(&OneMax, &env, Some(&optimum));
+    assert_eq!(easy.fitness.len(), 300);
+    assert_eq!(easy.distance.len(), 300);
+    assert!(easy.correlation < -0.99, "{}", easy.correlation);
+    let deceptive = landscape.fitness_distance(&Trap, &env, Some(&optimum));
+    assert!(deceptive.correlation > 0.99, "{}", deceptive.correlation);
+    // without an optimum the fittest sample is the reference
+    let fittest = landscape.fitness_distance(&OneMax, &env, None);
+    assert!(fittest.correlation < 0.0, "{}", fittest.correlation);
+}
+
+
+
+#[test]
+fn random_walks_on_a_smooth_landscape_are_correlated() {
+    let report = Landscape::new()
+        .samples(200)
+        .walks(8, 200)
+        .max_lag(5)
+        .analyze(&OneMax, &environment(), None);
+    assert_eq!(report.walks.len(), 8);
+    assert!(report.walks.iter().all(|x| x.fitness.len() == 201 && x.autocorrelation.len() == 5));
+    assert_eq!(report.autocorrelation.len(), 5);
+    assert!(report.autocorrelation[0] > 0.5, "{:?}", report.autocorrelation);
+    assert!(report.autocorrelation[0] > report.autocorrelation[4], "{:?}", report.autocorrelation);
+    assert!(report.walks.iter().all(|x| x.correlation_length > 0.0));
+    let neutrality = &report.neutrality;
+    assert!(neutrality.ratio > 0.0 && neutrality.ratio < 1.0, "{:?}", neutrality);
+    assert!((neutrality.ratio + neutrality.better + neutrality.worse - 1.0).abs() < 1e-5);
+    // the report is plain data which can be saved and read back
+    let json = serde_json::to_string(&report).unwrap();
+    let back: LandscapeReport = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.walks.len(), 8);
+}
+
+
+
+#[test]
+fn a_flat_landscape_is_all_neutral() {
+    let report = Landscape::new()
+        .samples(100)
+        .walks(2, 20)
+        .analyze(&Flat, &environment(), None);
+    assert_eq!(report.neutrality.ratio, 1.0);
+    assert_eq!(report.fitness_distance.correlation, 0.0);
+    assert!(report.autocorrelation.iter().all(|x| *x == 0.0));
+}
+
+
+
+#[test]
+fn diversity_is_the_mean_pairwise_distance() {
+    let env = environment();
+    let (zeros, ones) = (BitString::new(vec![false; 50]), BitString::new(vec![true; 50]));
+    assert_eq!(diversity(&[&zeros], &env), 0.0);
+    assert_eq!(diversity(&[&zeros, &zeros, &zeros], &env), 0.0);
+    assert!((diversity(&[&zeros, &ones, &ones], &env) - 2.0 / 3.0).abs() < 1e-5);
+    let mut population = Population::<BitString, BitStringEnvironment, OneMax>::new()
+        .size(50)
+        .constrain(BitStringEnvironment::new().set_length(50).set_mutation_rate(0.02))
+        .populate_base()
+        .track_diversity(true)
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.5,
+            distance: 0.2,
+            species_target: 5
+        });
+    for _ in 0..5 {
+        population.train().unwrap();
+    }
+    assert!(population.history().iter().all(|x| x.diversity > 0.0 && x.diversity <= 1.0));
+    // random bit strings differ in about half their bits
+    assert!((population.history()[0].diversity - 0.5).abs() < 0.1, "{}", population.history()[0].diversity);
+}
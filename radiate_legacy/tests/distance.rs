@@ -0,0 +1,125 @@
+extern crate radiate_legacy;
+
+use std::cell::RefCell;
+use std::sync::{Arc, RwLock};
+use radiate_legacy::prelude::*;
+
+
+
+/// one max
+pub struct OneMax;
+
+impl Problem<BitString> for OneMax {
+
+    fn empty() -> Self { OneMax }
+
+    fn solve(&self, member: &mut BitString) -> f32 {
+        member.ones() as f32
+    }
+}
+
+
+
+#[test]
+fn cache_holds_the_sampled_pairs() {
+    // exact caching computes nothing up front, only the distances asked for
+    let mut exact = DistanceCache::new();
+    exact.compute(10, &Sampling::Exact, |_, _| panic!("exact distances are computed on demand"));
+    assert!(exact.is_empty());
+    assert_eq!(exact.get_or_compute(2, 7, || 5.0), 5.0);
+    assert_eq!(exact.get(7, 2), Some(5.0));
+    assert_eq!(exact.get_or_compute(7, 2, || panic!("cached")), 5.0);
+    assert_eq!(exact.get(4, 4), Some(0.0));
+    exact.get_or_compute(1, 2, || 1.0);
+    assert_eq!(exact.len(), 2);
+    assert_eq!(exact.mean(), 3.0);
+    // without a sampling nothing asked for is kept
+    let uncached = DistanceCache::new();
+    assert_eq!(uncached.get_or_compute(2, 7, || 5.0), 5.0);
+    assert!(uncached.is_empty());
+    let mut sampled = DistanceCache::new();
+    sampled.compute(100, &Sampling::Sampled(3), |_, _| 1.0);
+    assert!(sampled.len() >= 150 && sampled.len() <= 300);
+    assert_eq!(sampled.mean(), 1.0);
+    assert_eq!(DistanceCache::new().mean(), 0.0);
+}
+
+
+
+#[test]
+fn cached_speciation_matches_uncached() {
+    let env = Arc::new(RwLock::new(BitStringEnvironment::new().set_length(32)));
+    let members = (0..60)
+        .map(|_| BitString::base(&mut env.write().unwrap()))
+        .collect::<Vec<_>>();
+    let speciate = |sampling: Option<Sampling>| {
+        let mut gen = Generation::<BitString, BitStringEnvironment>::new();
+        gen.members = members.iter()
+            .map(|x| Container::new(x.clone(), Lineage::new(0, Vec::new(), Vec::new())))
+            .collect();
+        if let Some(sampling) = sampling {
+            gen.cache_distances(&sampling, Arc::clone(&env));
+            assert!(gen.distances.is_empty());
+        }
+        gen.speciate(0.4, Arc::clone(&env));
+        // only the distances to the founders of new species are computed, and cached if exact
+        let founders = gen.species.len();
+        assert!(gen.distances.len() < 60 * founders);
+        gen.species.iter()
+            .map(|x| x.members.iter().map(|m| m.1).collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(speciate(None), speciate(Some(Sampling::Exact)));
+}
+
+
+
+#[test]
+fn most_different_parents() {
+    // one species of ten members, all as fit as each other, whose distance is how far apart their indexes are
+    let env = BitStringEnvironment::new().set_length(8);
+    let mut species = Niche::<BitString, BitStringEnvironment>::new(&BitString::base(&mut env.clone()), 1.0, 0);
+    species.members.extend((1..10).map(|i| NicheMember(1.0, i)));
+    species.calculate_total_adjusted_fitness();
+    let families = vec![&species];
+    let asked = RefCell::new(Vec::new());
+    for _ in 0..200 {
+        asked.borrow_mut().clear();
+        let (one, two) = ParentalCriteria::MostDifferent(3)
+            .pick_parents_with(0.0, &families, |one, two| {
+                let between = (one.2 as f32 - two.2 as f32).abs();
+                asked.borrow_mut().push(between);
+                between
+            })
+            .unwrap();
+        // every candidate is measured against the first parent and the furthest one is picked
+        let asked = asked.borrow();
+        assert_eq!(asked.len(), 3);
+        assert_eq!((one.2 as f32 - two.2 as f32).abs(), asked.iter().cloned().fold(0.0, f32::max));
+    }
+}
+
+
+
+#[test]
+fn most_different_parents_evolve() {
+    let mut population = Population::<BitString, BitStringEnvironment, OneMax>::new()
+        .size(100)
+        .constrain(BitStringEnvironment::new()
+            .set_length(32)
+            .set_mutation_rate(0.03))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.75,
+            distance: 0.3,
+            species_target: 5
+        })
+        .dynamic_distance(true)
+        .distance_cache(Sampling::Sampled(10))
+        .parental_criteria(ParentalCriteria::MostDifferent(3));
+    let report = population.run(|_, _, num| num == 50).unwrap();
+    let curve = report.fitness_curve();
+    assert!(curve[curve.len() - 1] > curve[0]);
+    assert!(population.stats().unwrap().diversity > 0.0);
+}
@@ -0,0 +1,106 @@
+extern crate radiate_legacy;
+
+use std::time::Duration;
+use radiate_legacy::prelude::*;
+use radiate_legacy::engine::{stats, random};
+
+
+
+/// one max
+pub struct OneMax;
+
+impl Problem<BitString> for OneMax {
+
+    fn empty() -> Self { OneMax }
+
+    fn solve(&self, member: &mut BitString) -> f32 {
+        member.ones() as f32
+    }
+}
+
+
+
+fn population(seed: u64, mutation_rate: f32) -> Population<BitString, BitStringEnvironment, OneMax> {
+    Population::<BitString, BitStringEnvironment, OneMax>::new()
+        .size(30)
+        .seed(seed)
+        .constrain(BitStringEnvironment::new()
+            .set_length(32)
+            .set_mutation_rate(mutation_rate))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.75,
+            distance: 0.3,
+            species_target: 5
+        })
+}
+
+
+
+fn report(bests: &[f32], target: Option<f32>) -> ExperimentReport {
+    ExperimentReport {
+        target,
+        runs: bests.iter()
+            .enumerate()
+            .map(|(i, best)| ExperimentRun {
+                seed: i as u64,
+                curve: vec![*best - 1.0, *best],
+                best: *best,
+                evaluations: 0,
+                elapsed: Duration::default()
+            })
+            .collect()
+    }
+}
+
+
+
+#[test]
+fn aggregate_statistics() {
+    assert_eq!(stats::quantile(&[3.0, 1.0, 2.0, 4.0], 0.5), 2.5);
+    assert_eq!(stats::quantile(&[], 0.5), 0.0);
+    assert!((random::normal_cdf(1.96) - 0.975).abs() < 1e-3);
+    assert!((random::normal_cdf(0.0) - 0.5).abs() < 1e-6);
+    let report = report(&[1.0, 2.0, 3.0, 4.0, 5.0], Some(4.0));
+    assert_eq!(report.median(), 3.0);
+    assert_eq!(report.quartiles(), (2.0, 4.0));
+    assert_eq!(report.iqr(), 2.0);
+    assert_eq!(report.success_rate(), 0.4);
+    assert_eq!(report.generations_to_target(), vec![1, 0]);
+    assert_eq!(report.median_curve(), vec![2.0, 3.0]);
+}
+
+
+
+#[test]
+fn rank_sum_tells_setups_apart() {
+    let low = report(&(0..20).map(|x| x as f32).collect::<Vec<_>>(), None);
+    let high = report(&(0..20).map(|x| x as f32 + 15.0).collect::<Vec<_>>(), None);
+    let test = high.rank_sum(&low);
+    assert!(test.z > 0.0);
+    assert!(test.p_value < 0.01);
+    assert_eq!(test.u + low.rank_sum(&high).u, 400.0);
+    let same = low.rank_sum(&low);
+    assert_eq!(same.z, 0.0);
+    assert!(same.p_value > 0.99);
+}
+
+
+
+#[test]
+fn experiment_runs_every_seed() {
+    for parallel in [true, false].iter() {
+        let report = Experiment::new(6)
+            .seed(10)
+            .target(32.0)
+            .parallel(*parallel)
+            .run(|seed| population(seed, 0.03), |_, fit, num| fit == 32.0 || num == 200);
+        assert_eq!(report.runs.len(), 6);
+        assert_eq!(report.runs.iter().map(|x| x.seed).collect::<Vec<_>>(), (10..16).collect::<Vec<_>>());
+        assert!(report.runs.iter().all(|x| !x.curve.is_empty() && x.evaluations == 30 * x.curve.len()));
+        assert!(report.success_rate() > 0.5);
+        assert!(report.median() >= report.quartiles().0);
+        assert!(!format!("{}", report).is_empty());
+    }
+}
@@ -0,0 +1,61 @@
+extern crate radiate_legacy;
+
+use radiate_legacy::prelude::*;
+
+
+
+/// count the ones in the bit string
+pub struct OneMax;
+
+impl Problem<BitString> for OneMax {
+
+    fn empty() -> Self { OneMax }
+
+    fn solve(&self, member: &mut BitString) -> f32 {
+        member.ones() as f32
+    }
+}
+
+
+
+#[test]
+fn lineage_is_recorded() {
+    let mut population = Population::<BitString, BitStringEnvironment, OneMax>::new()
+        .size(50)
+        .constrain(BitStringEnvironment::new()
+            .set_length(32)
+            .set_mutation_rate(0.05))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.5,
+            distance: 0.5,
+            species_target: 5
+        })
+        .operators(Pipeline::genome(0.5, 0.5))
+        .track_lineage(true);
+    for _ in 0..10 {
+        population.train().unwrap();
+    }
+    let genealogy = population.genealogy().unwrap();
+    assert!(genealogy.len() >= 50);
+    // every recorded parent was born before its child and every member has a species
+    for ancestor in genealogy.members.values() {
+        assert!(ancestor.lineage.species.is_some());
+        for parent in ancestor.lineage.parents.iter() {
+            assert!(genealogy.get(*parent).unwrap().lineage.birth < ancestor.lineage.birth);
+        }
+    }
+    // the best member of the last recorded generation can be traced back to the first generation
+    let champion = genealogy.members.values()
+        .filter(|x| x.last_seen == 9)
+        .max_by(|a, b| a.fitness.partial_cmp(&b.fitness).unwrap())
+        .unwrap();
+    let ancestry = genealogy.ancestry(champion.lineage.id);
+    assert!(ancestry.members.values().any(|x| x.lineage.birth == 0));
+    // and the whole thing can be exported
+    let graphml = ancestry.to_graphml();
+    assert_eq!(graphml.matches("<node ").count(), ancestry.len());
+    let json = genealogy.to_json().unwrap();
+    assert_eq!(serde_json::from_str::<Genealogy>(&json).unwrap(), *genealogy);
+}
@@ -0,0 +1,95 @@
+extern crate radiate_legacy;
+
+use std::sync::{Arc, RwLock};
+use radiate_legacy::prelude::*;
+
+
+
+/// one max
+pub struct OneMax;
+
+impl Problem<BitString> for OneMax {
+
+    fn empty() -> Self { OneMax }
+
+    fn solve(&self, member: &mut BitString) -> f32 {
+        member.ones() as f32
+    }
+}
+
+
+
+fn generation(env: &Arc<RwLock<BitStringEnvironment>>) -> Generation<BitString, BitStringEnvironment> {
+    let mut gen = Generation::new();
+    gen.members = (0..50)
+        .map(|_| Container::new(BitString::base(&mut env.write().unwrap()), Lineage::new(0, Vec::new(), Vec::new())))
+        .collect();
+    gen
+}
+
+
+
+#[test]
+fn species_point_at_their_members() {
+    let env = Arc::new(RwLock::new(BitStringEnvironment::new().set_length(32)));
+    let mut gen = generation(&env);
+    gen.optimize(Arc::new(RwLock::new(OneMax)));
+    gen.speciate(0.3, Arc::clone(&env));
+    assert!(!gen.species.is_empty());
+    let total = gen.species.iter().map(|x| x.members.len()).sum::<usize>();
+    assert_eq!(total, gen.members.len());
+    for spec in gen.species.iter() {
+        for NicheMember(_, index) in spec.members.iter() {
+            assert_eq!(gen.members[*index].species, Some(spec.niche_id));
+        }
+        let (fit, index) = spec.fittest();
+        assert_eq!(fit, gen.members[index].fitness_score / spec.members.len() as f32);
+    }
+}
+
+
+
+#[test]
+fn next_generation_keeps_survivors() {
+    let env = Arc::new(RwLock::new(BitStringEnvironment::new().set_length(32)));
+    let mut gen = generation(&env);
+    gen.survival_criteria = SurvivalCriteria::TopNumber(5);
+    gen.optimize(Arc::new(RwLock::new(OneMax)));
+    gen.speciate(0.3, Arc::clone(&env));
+    let mut scores = gen.members.iter().map(|x| x.fitness_score).collect::<Vec<_>>();
+    scores.sort_by(|a, b| b.partial_cmp(a).unwrap());
+    let species = gen.species.len();
+    let config = Config { inbreed_rate: 0.001, crossover_rate: 0.5, distance: 0.3, species_target: 5 };
+    let next = gen.create_next_generation(50, config, Arc::clone(&env)).unwrap();
+    assert_eq!(next.members.len(), 50);
+    assert_eq!(next.index, 1);
+    // the species carry over to the next generation, ready to be filled again
+    assert_eq!(next.species.len(), species);
+    assert!(next.species.iter().all(|x| x.members.is_empty()));
+    let survivors = next.members.iter().filter(|x| x.lineage.birth == 0).collect::<Vec<_>>();
+    assert_eq!(survivors.len(), 5);
+    for survivor in survivors {
+        assert!(survivor.member.ones() as f32 >= scores[4]);
+        assert_eq!(survivor.age, 1);
+    }
+}
+
+
+
+#[test]
+#[allow(deprecated)]
+fn locked_survivors_still_work() {
+    let env = Arc::new(RwLock::new(BitStringEnvironment::new().set_length(32)));
+    let mut gen = generation(&env);
+    gen.optimize(Arc::new(RwLock::new(OneMax)));
+    gen.speciate(0.3, Arc::clone(&env));
+    // survivors picked from species behind locks are copies of the fittest members
+    let families = gen.species.iter()
+        .map(|x| Arc::new(RwLock::new(x.clone())))
+        .collect::<Vec<Family<BitString, BitStringEnvironment>>>();
+    let fittest = SurvivalCriteria::Fittest.pick_survivors(&mut gen.members, &families).unwrap();
+    assert_eq!(fittest.len(), families.len());
+    let best = gen.members.iter().map(|x| x.fitness_score).fold(f32::MIN, f32::max);
+    let top = SurvivalCriteria::TopNumber(1).pick_survivors(&mut gen.members, &families).unwrap();
+    assert_eq!(top[0].read().unwrap().ones() as f32, best);
+}
@@ -0,0 +1,112 @@
+extern crate radiate_legacy;
+
+use std::sync::{Arc, RwLock};
+use radiate_legacy::prelude::*;
+
+
+
+/// count the ones in the bit string
+pub struct OneMax;
+
+impl Problem<BitString> for OneMax {
+
+    fn empty() -> Self { OneMax }
+
+    fn solve(&self, member: &mut BitString) -> f32 {
+        member.ones() as f32
+    }
+}
+
+
+
+#[test]
+fn onemax() {
+    let top = Population::<BitString, BitStringEnvironment, OneMax>::new()
+        .size(100)
+        .constrain(BitStringEnvironment::new()
+            .set_length(32)
+            .set_mutation_rate(0.05))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.5,
+            distance: 0.5,
+            species_target: 5
+        })
+        .run(|_, fit, num| fit == 32.0 || num == 300)
+        .unwrap().champion;
+    assert_eq!(top.ones(), 32);
+}
+
+
+
+#[test]
+fn permutation_crossovers_stay_valid() {
+    for crossover in [PermutationCrossover::Order, PermutationCrossover::PartiallyMapped, PermutationCrossover::Cycle].iter() {
+        let env = Arc::new(RwLock::new(PermutationEnvironment::new()
+            .set_length(20)
+            .set_crossover(*crossover)));
+        for _ in 0..100 {
+            let one = Permutation::random(20);
+            let two = Permutation::random(20);
+            let mut child = Permutation::crossover(&one, &two, Arc::clone(&env), 1.0).unwrap();
+            child.order.sort();
+            assert_eq!(child.order, (0..20).collect::<Vec<_>>(), "{:?} produced an invalid permutation", crossover);
+        }
+    }
+}
+
+
+
+/// the sphere function shifted to (0.5, 0.5, ..., 0.5)
+pub struct Sphere;
+
+impl Problem<RealVector> for Sphere {
+
+    fn empty() -> Self { Sphere }
+
+    fn solve(&self, member: &mut RealVector) -> f32 {
+        -member.values.iter().map(|x| (x - 0.5).powi(2)).sum::<f32>()
+    }
+}
+
+
+
+#[test]
+fn real_vector_pipeline() {
+    let top = Population::<RealVector, RealVectorEnvironment, Sphere>::new()
+        .size(100)
+        .constrain(RealVectorEnvironment::new()
+            .set_length(5)
+            .set_bounds(-2.0, 2.0)
+            .set_mutation(RealMutation::Polynomial(20.0)))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.0,
+            distance: 0.5,
+            species_target: 5
+        })
+        .operators(Pipeline::genome(0.9, 0.3))
+        .run(|_, fit, num| fit > -1e-3 || num == 500)
+        .unwrap().champion;
+    assert!(Sphere.solve(&mut top.clone()) > -1e-3, "pipeline did not converge: {:?}", top);
+}
+
+
+
+#[test]
+fn failed_operators_fall_back_to_the_parent() {
+    let mut population = Population::<RealVector, RealVectorEnvironment, Sphere>::new()
+        .size(50)
+        .constrain(RealVectorEnvironment::new()
+            .set_length(5)
+            .set_bounds(-2.0, 2.0))
+        .populate_base()
+        .operators(Pipeline::genome(0.9, 0.0)
+            .mutate("broken", 1.0, |_: &RealVector, _| None));
+    population.run(|_, _, num| num == 3).unwrap();
+    let stats = population.stats().unwrap();
+    assert_eq!(stats.size, 50);
+    assert!(stats.best.is_finite());
+}
@@ -7,7 +7,7 @@ use radiate::prelude::*;
 #[test]
 fn helloworld() -> Result<(), Box<dyn Error>> {
     let thread_time = Instant::now();
-    let (top, _) = Population::<Hello, HelloEnv, World>::new()
+    let top = Population::<Hello, HelloEnv, World>::new()
         .size(100)
         .populate_base()
         .dynamic_distance(true)
@@ -23,7 +23,7 @@ fn helloworld() -> Result<(), Box<dyn Error>> {
         .run(|model, fit, num| {
             println!("Generation: {} score: {:.3?}\t{:?}", num, fit, model.as_string());
             fit == 12.0 || num == 500
-        })?;
+        })?.champion;
         
 
     println!("\nTime in millis: {}, solution: {:?}", thread_time.elapsed().as_millis(), top.as_string());
@@ -0,0 +1,96 @@
+extern crate radiate_legacy;
+
+use radiate_legacy::prelude::*;
+
+
+
+fn population() -> Population<RealVector, RealVectorEnvironment, Rated> {
+    Population::<RealVector, RealVectorEnvironment, Rated>::new()
+        .size(50)
+        .constrain(RealVectorEnvironment::new()
+            .set_length(2)
+            .set_mutation(RealMutation::Gaussian(0.1)))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.5,
+            distance: 0.5,
+            species_target: 5
+        })
+}
+
+
+
+/// a person who likes members close to the origin, but only gets to the first few candidates
+fn rate(candidates: &[Candidate<'_, RealVector>], count: usize) -> Vec<(MemberId, f32)> {
+    candidates.iter()
+        .take(count)
+        .map(|x| (x.id, -x.member.values.iter().map(|v| v.powi(2)).sum::<f32>()))
+        .collect()
+}
+
+
+
+#[test]
+fn bad_ratings_leave_the_generation_alone() {
+    let mut population = population();
+    let candidates = population.candidates().iter().map(|x| x.id).collect::<Vec<_>>();
+    assert_eq!(candidates.len(), 50);
+    assert!(population.rate(&[]).is_err());
+    assert!(population.rate(&[(candidates[0], 1.0), (u64::MAX, 2.0)]).is_err());
+    assert!(population.members_mut().iter().all(|x| !x.predicted && x.fitness_score == 0.0));
+    assert_eq!(population.candidates().iter().map(|x| x.id).collect::<Vec<_>>(), candidates);
+    assert!(population.history().is_empty());
+}
+
+
+
+#[test]
+fn partial_ratings_are_filled_in() {
+    let mut population = population().fill_in(FillIn::Worst);
+    let ratings = vec![(population.candidates()[0].id, 3.0), (population.candidates()[1].id, 1.0)];
+    let first = population.candidates().iter().map(|x| x.id).collect::<Vec<_>>();
+    let (top, _) = population.rate(&ratings).unwrap();
+    assert_eq!(top, 3.0);
+    assert_eq!(population.evaluations(), 2);
+    assert_eq!(population.history().len(), 1);
+    // the next generation has been bred and is waiting to be rated
+    let next = population.candidates().iter().map(|x| x.id).collect::<Vec<_>>();
+    assert!(next.iter().any(|id| !first.contains(id)));
+}
+
+
+
+#[test]
+fn the_last_rating_of_a_member_wins() {
+    let mut population = population();
+    let (one, two) = (population.candidates()[0].id, population.candidates()[1].id);
+    let ratings = vec![(one, 1.0), (two, 3.0), (one, 5.0)];
+    FillIn::Mean.apply(population.members_mut(), &ratings, None).unwrap();
+    let members = population.members_mut();
+    assert_eq!(members[0].fitness_score, 5.0);
+    assert!(members[2..].iter().all(|x| x.predicted && x.fitness_score == 4.0));
+    FillIn::Worst.apply(members, &ratings, None).unwrap();
+    assert!(members[2..].iter().all(|x| x.fitness_score == 3.0));
+}
+
+
+
+#[test]
+fn evolve_from_ratings() {
+    let mut population = population()
+        .fill_in(FillIn::Model)
+        .surrogate(Surrogate::new(|member: &RealVector| member.values.clone()).model(Model::Knn(3)));
+    let mut best = f32::MIN;
+    for _ in 0..200 {
+        let ratings = rate(&population.candidates(), 10);
+        best = population.rate(&ratings).unwrap().0;
+        if best > -1e-3 {
+            break;
+        }
+    }
+    assert!(best > -1e-3, "{}", best);
+    let surrogate = population.get_surrogate().unwrap();
+    assert!(surrogate.predictions > 0);
+    assert_eq!(surrogate.evaluations, population.evaluations());
+}
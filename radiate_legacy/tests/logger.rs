@@ -0,0 +1,118 @@
+extern crate radiate_legacy;
+
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use radiate_legacy::prelude::*;
+
+
+
+/// one max
+pub struct OneMax;
+
+impl Problem<BitString> for OneMax {
+
+    fn empty() -> Self { OneMax }
+
+    fn solve(&self, member: &mut BitString) -> f32 {
+        member.ones() as f32
+    }
+}
+
+
+
+/// a log which can still be read after the population takes the logger
+#[derive(Clone, Default)]
+struct Shared(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Shared {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+
+
+/// a log which can't be written to
+struct Broken;
+
+impl Write for Broken {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Err(io::Error::other("broken"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+
+
+fn population() -> Population<BitString, BitStringEnvironment, OneMax> {
+    Population::<BitString, BitStringEnvironment, OneMax>::new()
+        .size(50)
+        .constrain(BitStringEnvironment::new()
+            .set_length(32)
+            .set_mutation_rate(0.05))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.75,
+            distance: 0.3,
+            species_target: 5
+        })
+}
+
+
+
+#[test]
+fn log_reads_back_every_generation() {
+    let buffer = Shared::default();
+    let mut population = population().log_to(RunLogger::new(buffer.clone()));
+    let report = population.run(|_, _, num| num == 9).unwrap();
+    assert_eq!(population.logger().unwrap().lines(), 10);
+    let bytes = buffer.0.lock().unwrap().clone();
+    let log = RunLog::from_reader(&bytes[..]).unwrap();
+    assert_eq!(log.len(), 10);
+    assert_eq!(log.fitness_curve(), report.fitness_curve());
+    for (entry, stats) in log.entries.iter().zip(report.history.iter()) {
+        assert_eq!(entry.stats, *stats);
+        assert_eq!(entry.species.len(), stats.species);
+        assert_eq!(entry.species.iter().map(|x| x.size).sum::<usize>(), 50);
+        assert_eq!(entry.champion.as_ref().unwrap().fitness, stats.best);
+        assert_eq!(entry.config.species_target, 5);
+    }
+    let last = log.entries.last().unwrap();
+    assert!(last.species.iter().any(|x| Some(x.id) == last.champion.as_ref().unwrap().species));
+    assert_eq!(log.generations_to(f32::MIN), Some(0));
+    assert_eq!(log.generations_to(33.0), None);
+}
+
+
+
+#[test]
+fn log_to_file() {
+    let path = std::env::temp_dir().join("radiate_legacy_run_log.jsonl");
+    let path = path.to_str().unwrap();
+    let mut population = population().log_to(RunLogger::to_file(path).unwrap());
+    population.run(|_, _, num| num == 4).unwrap();
+    let log = RunLog::load(path).unwrap();
+    assert_eq!(log.len(), 5);
+    assert_eq!(log.species_counts().len(), 5);
+    std::fs::remove_file(path).unwrap();
+}
+
+
+
+#[test]
+fn broken_log_does_not_stop_the_run() {
+    let mut population = population().log_to(RunLogger::new(Broken));
+    let report = population.run(|_, _, num| num == 4).unwrap();
+    assert_eq!(report.generations(), 5);
+    let logger = population.logger().unwrap();
+    assert_eq!(logger.lines(), 0);
+    assert!(logger.error().is_some());
+}
@@ -0,0 +1,72 @@
+extern crate radiate_legacy;
+
+use radiate_legacy::prelude::*;
+
+
+
+/// the behaviour of a vector is where it is, and it is better the closer it is to the origin
+pub struct Position;
+
+impl Behavior<RealVector> for Position {
+
+    fn empty() -> Self { Position }
+
+    fn evaluate(&self, member: &mut RealVector) -> (f32, Vec<f32>) {
+        let distance = member.values.iter().map(|x| x * x).sum::<f32>().sqrt();
+        (2.0 - distance, member.values.clone())
+    }
+}
+
+
+
+fn search(tessellation: Tessellation) -> Archive<RealVector> {
+    MapElites::<RealVector, RealVectorEnvironment, Position>::new()
+        .constrain(RealVectorEnvironment::new()
+            .set_length(2)
+            .set_bounds(-1.0, 1.0)
+            .set_mutation_rate(1.0))
+        .tessellation(tessellation)
+        .initial(50)
+        .batch(50)
+        .run(|archive, num| archive.coverage() > 0.95 || num == 200)
+        .unwrap()
+}
+
+
+
+#[test]
+fn map_elites_grid() {
+    let archive = search(Tessellation::grid(vec![10, 10], vec![(-1.0, 1.0), (-1.0, 1.0)]));
+    assert!(archive.coverage() > 0.9, "coverage too low: {}", archive.coverage());
+    assert!(archive.qd_score() > 0.0);
+    // every elite is in the cell its descriptor says it should be in
+    for (cell, elite) in archive.elites.iter() {
+        assert_eq!(archive.tessellation.cell(&elite.descriptor), Some(*cell));
+    }
+    let json = serde_json::to_string(&archive).unwrap();
+    assert_eq!(serde_json::from_str::<Archive<RealVector>>(&json).unwrap(), archive);
+}
+
+
+
+#[test]
+fn map_elites_cvt() {
+    let tessellation = Tessellation::cvt(30, vec![(-1.0, 1.0), (-1.0, 1.0)], 3000).unwrap();
+    assert_eq!(tessellation.cells(), 30);
+    let archive = search(tessellation);
+    assert!(archive.coverage() > 0.9, "coverage too low: {}", archive.coverage());
+}
+
+
+
+#[test]
+fn cvt_bounds_are_validated() {
+    assert!(Tessellation::cvt(0, vec![(-1.0, 1.0)], 100).is_err());
+    assert!(Tessellation::cvt(5, vec![(1.0, -1.0)], 100).is_err());
+    assert!(Tessellation::cvt(5, vec![(-1.0, 1.0), (f32::NAN, 1.0)], 100).is_err());
+    // a flat dimension keeps every centroid at its value
+    match Tessellation::cvt(5, vec![(-1.0, 1.0), (0.5, 0.5)], 100).unwrap() {
+        Tessellation::Cvt(centroids) => assert!(centroids.iter().all(|x| x[1] == 0.5)),
+        _ => panic!("expected a cvt")
+    }
+}
@@ -0,0 +1,196 @@
+extern crate radiate_legacy;
+extern crate rand;
+
+use std::sync::{Arc, RwLock};
+use rand::Rng;
+use radiate_legacy::prelude::*;
+
+
+
+/// a list of bits which can grow and shrink, only the first 8 bits count so anything past them is bloat
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bits {
+    pub bits: Vec<bool>
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BitsEnv;
+
+impl Envionment for BitsEnv {}
+
+impl Genome<Bits, BitsEnv> for Bits {
+
+    fn crossover(one: &Bits, two: &Bits, _: Arc<RwLock<BitsEnv>>, crossover_rate: f32) -> Option<Bits> {
+        let mut r = rand::thread_rng();
+        let mut bits = one.bits.clone();
+        if r.gen::<f32>() < crossover_rate {
+            let cut = r.gen_range(0, bits.len() + 1);
+            bits.truncate(cut);
+            bits.extend(two.bits.iter().skip(cut));
+        } else {
+            let index = r.gen_range(0, bits.len());
+            bits[index] = !bits[index];
+            // growing is more likely than shrinking, so without parsimony the lists drift longer
+            if r.gen::<f32>() < 0.3 {
+                bits.push(r.gen());
+            } else if r.gen::<f32>() < 0.1 && bits.len() > 1 {
+                bits.pop();
+            }
+        }
+        if bits.is_empty() {
+            bits.push(false);
+        }
+        Some(Bits { bits })
+    }
+
+    fn distance(one: &Bits, two: &Bits, _: Arc<RwLock<BitsEnv>>) -> f32 {
+        (one.bits.len() as f32 - two.bits.len() as f32).abs()
+    }
+
+    fn complexity(member: &Bits) -> f32 {
+        member.bits.len() as f32
+    }
+
+    fn base(_: &mut BitsEnv) -> Bits {
+        Bits { bits: vec![false] }
+    }
+}
+
+
+
+pub struct FirstEight;
+
+impl Problem<Bits> for FirstEight {
+
+    fn empty() -> Self { FirstEight }
+
+    fn solve(&self, member: &mut Bits) -> f32 {
+        member.bits.iter().take(8).filter(|x| **x).count() as f32
+    }
+}
+
+
+
+fn containers(members: &[(usize, f32)]) -> Vec<Container<Bits, BitsEnv>> {
+    members.iter()
+        .map(|(length, fitness)| {
+            let mut cont = Container::new(Bits { bits: vec![true; *length] }, Lineage::new(0, Vec::new(), Vec::new()));
+            cont.fitness_score = *fitness;
+            cont.objective = *fitness;
+            cont
+        })
+        .collect()
+}
+
+
+
+fn mean_length(parsimony: Option<Parsimony>) -> (f32, f32) {
+    let mut population = Population::<Bits, BitsEnv, FirstEight>::new()
+        .size(100)
+        .constrain(BitsEnv)
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.3,
+            distance: 2.0,
+            species_target: 5
+        });
+    if let Some(parsimony) = parsimony {
+        population = population.parsimony(parsimony);
+    }
+    let report = population.run(|_, _, num| num == 150).unwrap();
+    let lengths = population.members_mut().iter().map(|x| x.member.bits.len() as f32).collect::<Vec<_>>();
+    (lengths.iter().sum::<f32>() / lengths.len() as f32, report.fitness)
+}
+
+
+
+#[test]
+fn neat_complexity_counts_nodes_and_edges() {
+    let net = Neat::new().input_size(2).dense_pool(1, Activation::Sigmoid);
+    // 3 nodes and 2 edges
+    assert_eq!(Neat::complexity(&net), 5.0);
+    let bigger = Neat::new().input_size(2).dense_pool(3, Activation::Sigmoid);
+    assert_eq!(Neat::complexity(&bigger), 11.0);
+    assert_eq!(BitString::complexity(&BitString { bits: vec![true; 4] }), 0.0);
+}
+
+
+
+#[test]
+fn lexicographic_only_breaks_ties() {
+    let mut members = containers(&[(10, 3.0), (2, 3.0), (1, 2.0), (50, 5.0)]);
+    Parsimony::Lexicographic.apply(&mut members);
+    let scores = members.iter().map(|x| x.fitness_score).collect::<Vec<_>>();
+    assert!(scores[1] > scores[0]);
+    assert!(scores[0] > scores[2]);
+    assert!(scores[3] > scores[0]);
+    assert!(members.iter().all(|x| x.fitness_score <= x.objective));
+}
+
+
+
+#[test]
+fn linear_penalty_and_double_tournament() {
+    let mut members = containers(&[(10, 3.0), (2, 3.0)]);
+    Parsimony::Linear(0.1).apply(&mut members);
+    assert!((members[0].fitness_score - 2.0).abs() < 1e-5);
+    assert!((members[1].fitness_score - 2.8).abs() < 1e-5);
+    assert_eq!(members[0].objective, 3.0);
+
+    // scores stay between the lowest and highest fitness
+    let mut members = containers(&(1..=20).map(|x| (x, (x % 5) as f32)).collect::<Vec<_>>());
+    Parsimony::double_tournament().apply(&mut members);
+    assert!(members.iter().all(|x| x.fitness_score >= 0.0 && x.fitness_score <= 4.0));
+}
+
+
+
+#[test]
+fn double_tournament_prefers_small_members() {
+    // every member is as fit as the next, so only their size tells them apart
+    let mut members = containers(&(1..=100).map(|x| (x, 1.0)).collect::<Vec<_>>());
+    Parsimony::DoubleTournament { size: 3, probability: 1.0 }.apply(&mut members);
+    members.sort_by(|a, b| b.fitness_score.partial_cmp(&a.fitness_score).unwrap());
+    let top = members.iter().take(20).map(|x| x.member.bits.len()).sum::<usize>() as f32 / 20.0;
+    assert!(top < 40.0, "{}", top);
+}
+
+
+
+#[test]
+fn reports_keep_the_real_fitness() {
+    let mut population = Population::<Bits, BitsEnv, FirstEight>::new()
+        .size(50)
+        .constrain(BitsEnv)
+        .populate_base()
+        .hall_of_fame(5)
+        .track_lineage(true)
+        .parsimony(Parsimony::Linear(1.0))
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.3,
+            distance: 2.0,
+            species_target: 5
+        });
+    for _ in 0..20 {
+        let (top, _) = population.train().unwrap();
+        assert_eq!(population.stats().unwrap().best, top);
+    }
+    // a penalized score would be below zero for any member longer than its fitness
+    assert!(population.history().iter().all(|x| x.worst >= 0.0));
+    assert!(population.genealogy().unwrap().members.values().all(|x| x.fitness >= 0.0));
+    for famer in population.get_hall_of_fame().members.iter() {
+        assert_eq!(famer.fitness, FirstEight.solve(&mut famer.member.clone()));
+    }
+}
+
+
+
+#[test]
+fn parsimony_keeps_members_small() {
+    let (bloated, _) = mean_length(None);
+    let (linear, fitness) = mean_length(Some(Parsimony::Linear(0.1)));
+    assert!(linear < bloated, "{} {}", linear, bloated);
+    assert_eq!(fitness, 8.0);
+}
@@ -0,0 +1,92 @@
+extern crate radiate_legacy;
+
+use std::sync::{Arc, RwLock};
+use radiate_legacy::prelude::*;
+
+
+
+/// the negative sphere function
+pub struct Sphere;
+
+impl Problem<RealVector> for Sphere {
+
+    fn empty() -> Self { Sphere }
+
+    fn solve(&self, member: &mut RealVector) -> f32 {
+        -member.values.iter().map(|x| x.powi(2)).sum::<f32>()
+    }
+}
+
+
+
+/// members only behave differently if their values sum to something different
+fn probes() -> Probes<RealVector> {
+    Probes::new(vec![vec![1.0], vec![-2.0]], |member: &mut RealVector, input: &[f32]| {
+        vec![member.values.iter().sum::<f32>() * input[0]]
+    })
+}
+
+
+
+fn species_count(probes: Option<Probes<RealVector>>) -> usize {
+    let env = Arc::new(RwLock::new(RealVectorEnvironment::new().set_length(3).set_bounds(-1.0, 1.0)));
+    let mut gen = Generation::<RealVector, RealVectorEnvironment>::new();
+    gen.members = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0], vec![0.0, 0.0, 1.0], vec![1.0, 1.0, 1.0]]
+        .into_iter()
+        .map(|x| Container::new(RealVector::new(x), Lineage::new(0, Vec::new(), Vec::new())))
+        .collect();
+    gen.probes = probes.map(Arc::new);
+    gen.optimize(Arc::new(RwLock::new(Sphere)));
+    gen.speciate(0.1, Arc::clone(&env));
+    gen.species.len()
+}
+
+
+
+#[test]
+fn signatures_and_distances() {
+    let probes = probes();
+    let signature = probes.signature(&mut RealVector::new(vec![1.0, 2.0]));
+    assert_eq!(signature, vec![3.0, -6.0]);
+    assert_eq!(Probes::<RealVector>::behaviour_distance(&[0.0, 0.0], &[3.0, 4.0]), 12.5_f32.sqrt());
+    assert_eq!(Probes::<RealVector>::behaviour_distance(&[], &[1.0]), 0.0);
+    assert_eq!(probes.distance(Some(&[0.0]), Some(&[2.0]), || panic!("genome distance not needed")), 2.0);
+    assert_eq!(probes.distance(None, Some(&[2.0]), || 7.0), 7.0);
+    let blended = Probes::new(Vec::new(), |_: &mut RealVector, _: &[f32]| Vec::new()).blend(0.25);
+    assert_eq!(blended.distance(Some(&[0.0]), Some(&[2.0]), || 4.0), 3.5);
+    assert_eq!(blended.blend(2.0).blend, 1.0);
+}
+
+
+
+#[test]
+fn speciate_by_behaviour() {
+    // every member is genetically far apart, but the first three behave the same
+    assert_eq!(species_count(None), 4);
+    assert_eq!(species_count(Some(probes())), 2);
+    // a blend with the genome's distance splits them again
+    assert_eq!(species_count(Some(probes().blend(0.5))), 4);
+}
+
+
+
+#[test]
+fn population_with_behavioural_speciation() {
+    let mut population = Population::<RealVector, RealVectorEnvironment, Sphere>::new()
+        .size(100)
+        .constrain(RealVectorEnvironment::new()
+            .set_length(3)
+            .set_mutation(RealMutation::Gaussian(0.1)))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.5,
+            distance: 0.2,
+            species_target: 5
+        })
+        .dynamic_distance(true)
+        .behavioural_speciation(probes());
+    let report = population.run(|_, fit, num| fit > -1e-3 || num == 500).unwrap();
+    assert!(report.fitness > -1e-3, "did not solve: {:?}", report.champion);
+    assert!(!report.species.is_empty());
+}
@@ -0,0 +1,72 @@
+extern crate radiate_legacy;
+extern crate serde_json;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use radiate_legacy::prelude::*;
+
+
+
+/// one max, counting how many times it is solved
+pub struct OneMax {
+    calls: AtomicUsize
+}
+
+impl Problem<BitString> for OneMax {
+
+    fn empty() -> Self { OneMax { calls: AtomicUsize::new(0) } }
+
+    fn solve(&self, member: &mut BitString) -> f32 {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        member.ones() as f32
+    }
+}
+
+
+
+fn population() -> Population<BitString, BitStringEnvironment, OneMax> {
+    Population::<BitString, BitStringEnvironment, OneMax>::new()
+        .size(50)
+        .constrain(BitStringEnvironment::new()
+            .set_length(16)
+            .set_mutation_rate(0.05))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.75,
+            distance: 0.3,
+            species_target: 5
+        })
+}
+
+
+
+#[test]
+fn report_records_the_run() {
+    let mut population = population();
+    let report = population.run(|_, fit, num| fit == 16.0 || num == 300).unwrap();
+    assert_eq!(report.stop, StopReason::Satisfied);
+    assert_eq!(report.fitness, report.champion.ones() as f32);
+    assert_eq!(report.history.len(), report.fitness_curve().len());
+    assert_eq!(report.history.last().unwrap().best, report.fitness);
+    assert!(report.history.windows(2).all(|x| x[1].generation == x[0].generation + 1));
+    // every generation solves every member once
+    assert_eq!(report.evaluations, 50 * report.generations());
+    assert_eq!(report.evaluations, population.problem().read().unwrap().calls.load(Ordering::SeqCst));
+    assert!(!report.species.is_empty());
+    assert_eq!(report.species.iter().map(|x| x.size).sum::<usize>(), 50);
+    assert!(report.species.iter().any(|x| x.best == report.fitness));
+    assert_eq!(report.environment.length, 16);
+}
+
+
+
+#[test]
+fn report_round_trips_through_json() {
+    let mut population = population();
+    let report = population.run(|_, _, num| num == 4).unwrap();
+    assert_eq!(report.generations(), 5);
+    let json = report.to_json().unwrap();
+    let back: RunReport<BitString, BitStringEnvironment> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.champion, report.champion);
+    assert_eq!(back.to_json().unwrap(), json);
+}
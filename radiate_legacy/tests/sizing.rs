@@ -0,0 +1,127 @@
+extern crate radiate_legacy;
+
+use radiate_legacy::prelude::*;
+
+
+
+/// every member is as good as every other, so the run never improves
+pub struct Flat;
+
+impl Problem<BitString> for Flat {
+
+    fn empty() -> Self { Flat }
+
+    fn solve(&self, _: &mut BitString) -> f32 {
+        1.0
+    }
+}
+
+
+
+fn population(size: i32) -> Population<BitString, BitStringEnvironment, Flat> {
+    Population::<BitString, BitStringEnvironment, Flat>::new()
+        .size(size)
+        .constrain(BitStringEnvironment::new()
+            .set_length(16)
+            .set_mutation_rate(0.05))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.5,
+            distance: 0.3,
+            species_target: 5
+        })
+}
+
+
+
+fn sizes(report: &RunReport<BitString, BitStringEnvironment>) -> Vec<usize> {
+    report.history.iter().map(|x| x.size).collect()
+}
+
+
+
+fn schedule(generation: usize, _: &GenerationStats) -> i32 {
+    20 + 10 * generation as i32
+}
+
+
+
+#[test]
+fn scheduled_size() {
+    let report = population(20)
+        .sizing(Sizing::Schedule(schedule))
+        .run(|_, _, num| num == 4)
+        .unwrap();
+    assert_eq!(sizes(&report), vec![20, 30, 40, 50, 60]);
+}
+
+
+
+#[test]
+fn grow_on_stagnation() {
+    let report = population(20)
+        .sizing(Sizing::Adaptive { stagnation: 2, grow: 2.0, converged: 0.0, shrink: 1.0, min: 10, max: 80 })
+        .run(|_, _, num| num == 6)
+        .unwrap();
+    assert_eq!(sizes(&report), vec![20, 20, 20, 40, 40, 80, 80]);
+}
+
+
+
+#[test]
+fn shrink_as_fitness_converges() {
+    let report = population(40)
+        .survivor_criteria(SurvivalCriteria::TopNumber(30))
+        .sizing(Sizing::Adaptive { stagnation: 0, grow: 1.0, converged: 0.5, shrink: 0.5, min: 10, max: 80 })
+        .run(|_, _, num| num == 3)
+        .unwrap();
+    assert_eq!(sizes(&report), vec![40, 20, 10, 10]);
+}
+
+
+
+#[test]
+fn ipop_restarts_keep_the_hall_of_fame() {
+    let mut population = population(20)
+        .hall_of_fame(3)
+        .restart(Restart::Ipop { stagnation: 3, factor: 2.0, max_size: 80 });
+    let report = population.run(|_, _, num| num == 11).unwrap();
+    assert_eq!(sizes(&report), vec![20, 20, 20, 20, 40, 40, 40, 40, 80, 80, 80, 80]);
+    assert_eq!(population.restarts(), 3);
+    assert_eq!(population.get_hall_of_fame().len(), 3);
+    let generations = report.history.iter().map(|x| x.generation).collect::<Vec<_>>();
+    assert_eq!(generations, (0..12).collect::<Vec<_>>());
+}
+
+
+
+#[test]
+fn swapping_the_problem_resets_restart_stagnation() {
+    let mut population = population(20)
+        .restart(Restart::Fresh { stagnation: 3 });
+    for _ in 0..3 {
+        population.train().unwrap();
+    }
+    // one more stagnant generation would restart, but scores of the old problem don't count
+    population.swap_problem(Flat);
+    population.train().unwrap();
+    assert_eq!(population.restarts(), 0);
+    for _ in 0..3 {
+        population.train().unwrap();
+    }
+    assert_eq!(population.restarts(), 1);
+}
+
+
+
+#[test]
+fn restart_age_layers() {
+    let mut population = population(20)
+        .alps(Alps::new().layers(3).age_gap(2))
+        .restart(Restart::Fresh { stagnation: 2 });
+    let report = population.run(|_, _, num| num == 9).unwrap();
+    assert_eq!(report.generations(), 10);
+    assert!(population.restarts() >= 3);
+    assert!(report.history.windows(2).all(|x| x[1].generation > x[0].generation));
+}
@@ -0,0 +1,145 @@
+extern crate radiate_legacy;
+extern crate rand;
+
+use rand::Rng;
+use radiate_legacy::prelude::*;
+
+
+
+/// the sphere function shifted so the answer is at (1, 1, ..., 1),
+/// the score is negated because the engine maximizes
+pub struct Sphere;
+
+impl Problem<Vec<f32>> for Sphere {
+
+    fn empty() -> Self { Sphere }
+
+    fn solve(&self, member: &mut Vec<f32>) -> f32 {
+        -member.iter().map(|x| (x - 1.0).powi(2)).sum::<f32>()
+    }
+}
+
+
+
+#[test]
+fn cmaes_sphere() {
+    let report = Cmaes::<Vec<f32>, Sphere>::new()
+        .dimension(5)
+        .sigma(1.0)
+        .bounds(-5.0, 5.0)
+        .ipop(2, 2.0)
+        .run(|_, fit, num| fit > -1e-6 || num == 1000)
+        .unwrap();
+    assert!(report.fitness > -1e-6, "cmaes did not converge: {:?}", report.champion);
+    assert!(report.champion.iter().all(|x| (x - 1.0).abs() < 1e-2));
+    assert_eq!(report.fitness, report.fitness_curve().into_iter().fold(f32::MIN, f32::max));
+}
+
+
+
+#[test]
+fn differential_sphere() {
+    let report = DifferentialEvolution::<Vec<f32>, Sphere>::new()
+        .dimension(5)
+        .size(40)
+        .bounds(-5.0, 5.0)
+        .run(|_, fit, num| fit > -1e-4 || num == 2000)
+        .unwrap();
+    assert!(report.fitness > -1e-4, "differential evolution did not converge: {:?}", report.champion);
+    assert_eq!(report.stop, StopReason::Satisfied);
+    assert_eq!(report.evaluations, 40 * (report.generations() + 1));
+}
+
+
+
+/// the sphere solved on a noisy sample each time, the noise comes from the context so it
+/// is different for every member, solving on the full problem has no noise at all
+pub struct NoisySphere;
+
+impl Problem<Vec<f32>> for NoisySphere {
+
+    fn empty() -> Self { NoisySphere }
+
+    fn solve(&self, member: &mut Vec<f32>) -> f32 {
+        Sphere.solve(member)
+    }
+
+    fn solve_with_context(&self, member: &mut Vec<f32>, context: &mut EvalContext) -> f32 {
+        self.solve(member) + context.rng.gen_range(0.0, 1.0)
+    }
+
+    fn solve_full(&self, member: &mut Vec<f32>) -> f32 {
+        self.solve(member)
+    }
+}
+
+
+
+#[test]
+fn strategies_report_like_a_population() {
+    let cmaes = Cmaes::<Vec<f32>, NoisySphere>::new()
+        .dimension(3)
+        .size(6)
+        .ipop(1, 2.0)
+        .tolerance(1.0)
+        .run(|_, _, num| num == 1000)
+        .unwrap();
+    assert_eq!(cmaes.stop, StopReason::Exhausted);
+    assert_eq!(cmaes.history.iter().map(|x| x.size).collect::<Vec<_>>(), vec![6, 12]);
+    assert_eq!(cmaes.evaluations, 18);
+    assert_eq!(cmaes.fitness, Sphere.solve(&mut cmaes.champion.clone()));
+
+    let differential = DifferentialEvolution::<Vec<f32>, NoisySphere>::new()
+        .dimension(3)
+        .size(10)
+        .run(|_, _, num| num == 9)
+        .unwrap();
+    assert_eq!(differential.generations(), 10);
+    assert!(differential.history.iter().all(|x| x.best > x.worst));
+    assert_eq!(differential.fitness, Sphere.solve(&mut differential.champion.clone()));
+}
+
+
+
+/// almost flat, the score moves a little everywhere but never by more than 2e-6
+pub struct Flat;
+
+impl Problem<Vec<f32>> for Flat {
+
+    fn empty() -> Self { Flat }
+
+    fn solve(&self, member: &mut Vec<f32>) -> f32 {
+        member.iter().sum::<f32>().tanh() * 1e-6
+    }
+}
+
+
+
+#[test]
+fn cmaes_restarts_when_the_top_score_stops_moving() {
+    // the top score changes every generation but stays within the tolerance, so every search is stagnant after 5 generations
+    let report = Cmaes::<Vec<f32>, Flat>::new()
+        .dimension(3)
+        .size(6)
+        .ipop(1, 2.0)
+        .tolerance(1e-4)
+        .stagnation(5)
+        .run(|_, _, num| num == 1000)
+        .unwrap();
+    assert_eq!(report.stop, StopReason::Exhausted);
+    assert_eq!(report.history.iter().map(|x| x.size).collect::<Vec<_>>(), vec![6, 6, 6, 6, 6, 12, 12, 12, 12, 12]);
+}
+
+
+
+#[test]
+fn cmaes_needs_two_samples() {
+    assert!(Cmaes::<Vec<f32>, Sphere>::new().dimension(3).size(1).run(|_, _, _| true).is_err());
+    let report = Cmaes::<Vec<f32>, Sphere>::new()
+        .dimension(3)
+        .size(2)
+        .run(|_, _, num| num == 20)
+        .unwrap();
+    assert!(report.fitness.is_finite());
+    assert!(report.champion.iter().all(|x| x.is_finite()));
+}
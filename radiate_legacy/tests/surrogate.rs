@@ -0,0 +1,105 @@
+extern crate radiate_legacy;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use radiate_legacy::prelude::*;
+
+
+
+/// the negative sphere function, counting every time it is solved
+#[derive(Default)]
+pub struct Sphere {
+    pub calls: AtomicUsize
+}
+
+impl Problem<RealVector> for Sphere {
+
+    fn empty() -> Self { Sphere::default() }
+
+    fn solve(&self, member: &mut RealVector) -> f32 {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        -member.values.iter().map(|x| x.powi(2)).sum::<f32>()
+    }
+}
+
+
+
+fn population(model: Model) -> Population<RealVector, RealVectorEnvironment, Sphere> {
+    Population::<RealVector, RealVectorEnvironment, Sphere>::new()
+        .size(100)
+        .constrain(RealVectorEnvironment::new()
+            .set_length(3)
+            .set_mutation(RealMutation::Polynomial(20.0)))
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.5,
+            distance: 0.5,
+            species_target: 5
+        })
+        .surrogate(Surrogate::new(|member: &RealVector| member.values.clone())
+            .model(model)
+            .fraction(0.3)
+            .warmup(100)
+            .capacity(1000))
+}
+
+
+
+#[test]
+fn predictions_follow_the_samples() {
+    let mut knn = Surrogate::<RealVector>::new(|member: &RealVector| member.values.clone()).model(Model::Knn(2));
+    assert_eq!(knn.predict(&[0.0]), None);
+    knn.record(vec![0.0], 0.0);
+    knn.record(vec![1.0], 1.0);
+    knn.record(vec![10.0], 10.0);
+    assert_eq!(knn.predict(&[1.0]), Some(1.0));
+    let mid = knn.predict(&[0.5]).unwrap();
+    assert!((mid - 0.5).abs() < 1e-5);
+    let mut rbf = Surrogate::<RealVector>::new(|member: &RealVector| member.values.clone())
+        .model(Model::Rbf(0.1))
+        .capacity(2);
+    rbf.record(vec![0.0], 0.0);
+    rbf.record(vec![1.0], 1.0);
+    rbf.record(vec![10.0], 10.0);
+    // the oldest sample was dropped, and far from every sample the nearest one is used
+    assert_eq!(rbf.len(), 2);
+    assert_eq!(rbf.predict(&[-50.0]), Some(1.0));
+    assert!(rbf.predict(&[9.9]).unwrap() > 9.0);
+}
+
+
+
+#[test]
+fn surrogate_saves_evaluations() {
+    for model in [Model::Knn(5), Model::Rbf(0.05)].iter() {
+        let mut population = population(model.clone());
+        let top = population.run(|_, fit, num| fit > -1e-2 || num == 300).unwrap().champion;
+        assert!(top.values.iter().map(|x| x.powi(2)).sum::<f32>() < 1e-2, "did not solve with {:?}: {:?}", model, top);
+        let surrogate = population.get_surrogate().unwrap();
+        let calls = population.problem().read().unwrap().calls.load(Ordering::SeqCst);
+        assert_eq!(calls, surrogate.evaluations);
+        // the first generation warms the model up, after that only the screened fraction of each generation is solved
+        let generations = population.stats().unwrap().generation + 1;
+        let solved = (100.0_f32 * 0.3).ceil() as usize;
+        assert!(calls <= 100 + solved * (generations - 1), "{} calls in {} generations", calls, generations);
+        assert_eq!(surrogate.predictions, (100 - solved) * (generations - 1));
+    }
+}
+
+
+
+#[test]
+fn predictions_stay_out_of_the_reports() {
+    let mut population = population(Model::Knn(5)).hall_of_fame(20);
+    for _ in 0..5 {
+        population.train().unwrap();
+    }
+    // after the first generation only the screened fraction was solved, the stats only count those
+    let solved = (100.0_f32 * 0.3).ceil() as usize;
+    assert_eq!(population.history()[0].size, 100);
+    assert!(population.history().iter().skip(1).all(|x| x.size == solved));
+    let problem = Sphere::default();
+    for famer in population.get_hall_of_fame().members.iter() {
+        assert_eq!(famer.fitness, problem.solve(&mut famer.member.clone()));
+    }
+}
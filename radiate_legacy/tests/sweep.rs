@@ -0,0 +1,95 @@
+extern crate radiate_legacy;
+
+use radiate_legacy::prelude::*;
+
+
+
+/// count the ones in the bit string
+pub struct OneMax;
+
+impl Problem<BitString> for OneMax {
+
+    fn empty() -> Self { OneMax }
+
+    fn solve(&self, member: &mut BitString) -> f32 {
+        member.ones() as f32
+    }
+}
+
+
+
+fn sweep() -> Sweep<BitStringEnvironment> {
+    let config = Config {
+        inbreed_rate: 0.001,
+        crossover_rate: 0.5,
+        distance: 0.5,
+        species_target: 5
+    };
+    Sweep::new(config, BitStringEnvironment::new().set_length(16))
+        .crossover_rate(Range::Values(vec![0.2, 0.8]))
+        .parameter("mutation_rate", Range::Uniform(0.01, 0.1), |s, v| s.environment.mutation_rate = v)
+        .steps(2)
+        .repeats(2)
+}
+
+
+
+#[test]
+fn grid_sweep() {
+    let report = sweep()
+        .run(|setting, seed| setting.evolve::<BitString, OneMax>(OneMax, 50, 100, 16.0, seed))
+        .unwrap();
+    assert_eq!(report.rows.len(), 4);
+    assert!(report.rows.iter().all(|x| x.trials.len() == 2));
+    assert!(report.best().unwrap().mean_best() > 10.0);
+    let csv = report.to_csv();
+    assert_eq!(csv.lines().count(), 5);
+    assert_eq!(csv.lines().next().unwrap(), "crossover_rate,mutation_rate,repeats,mean_best,median_best,success_rate,mean_generations");
+    assert!(csv.contains("0.2,0.01,2,"));
+}
+
+
+
+#[test]
+fn random_and_evolutionary_sweeps() {
+    let report = sweep()
+        .search(Search::Random(3))
+        .run(|setting, seed| Trial::new(setting.environment.mutation_rate + seed as f32))
+        .unwrap();
+    assert_eq!(report.rows.len(), 3);
+    for row in report.rows.iter() {
+        assert!((row.median_best() - row.values[1].1 - 0.5).abs() < 1e-5);
+    }
+    let report = sweep()
+        .search(Search::Evolutionary { size: 4, generations: 2 })
+        .run(|setting, _| Trial::new(setting.config.crossover_rate))
+        .unwrap();
+    assert_eq!(report.rows.len(), 8);
+}
+
+
+
+#[test]
+fn empty_uniform_ranges_are_rejected() {
+    let result = sweep()
+        .parameter("mutation_rate", Range::Uniform(0.1, 0.1), |s, v| s.environment.mutation_rate = v)
+        .run(|setting, _| Trial::new(setting.config.crossover_rate));
+    assert!(result.is_err());
+}
+
+
+
+#[test]
+fn ranges_are_validated() {
+    assert!(Range::Uniform(0.0, 1.0).validate().is_ok());
+    assert!(Range::Uniform(1.0, 0.0).validate().is_err());
+    assert!(Range::Uniform(0.0, f32::NAN).validate().is_err());
+    assert!(Range::Values(Vec::new()).validate().is_err());
+    // a sweep row and an experiment aggregate their runs the same way
+    let row = SweepRow {
+        values: Vec::new(),
+        trials: vec![Trial { best: 3.0, generations: Some(4) }, Trial::new(1.0), Trial::new(2.0)]
+    };
+    assert_eq!(row.median_best(), 2.0);
+    assert!((row.success_rate() - 1.0 / 3.0).abs() < 1e-6);
+}
@@ -0,0 +1,173 @@
+extern crate radiate_legacy;
+extern crate rand;
+extern crate serde_json;
+
+use std::sync::{Arc, RwLock};
+use rand::Rng;
+use radiate_legacy::prelude::*;
+
+
+
+/// a list of numbers which grows by one every time it's mutated, no list can be longer than the max length.
+/// Lists a little too long can be repaired by cutting them down, ones far too long can't
+#[derive(Debug, Clone, PartialEq)]
+pub struct Numbers {
+    pub values: Vec<u8>
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NumbersEnv {
+    pub max_length: usize,
+    pub repairable: bool
+}
+
+impl Envionment for NumbersEnv {}
+
+impl Genome<Numbers, NumbersEnv> for Numbers {
+
+    fn crossover(one: &Numbers, two: &Numbers, _: Arc<RwLock<NumbersEnv>>, crossover_rate: f32) -> Option<Numbers> {
+        let mut r = rand::thread_rng();
+        let mut values = one.values.clone();
+        if r.gen::<f32>() < crossover_rate {
+            values.extend(two.values.iter().take(r.gen_range(0, 4)));
+        } else {
+            values.push(r.gen_range(0, 10));
+        }
+        Some(Numbers { values })
+    }
+
+    fn distance(one: &Numbers, two: &Numbers, _: Arc<RwLock<NumbersEnv>>) -> f32 {
+        (one.values.len() as f32 - two.values.len() as f32).abs()
+    }
+
+    fn validate(member: &Numbers, env: Arc<RwLock<NumbersEnv>>) -> bool {
+        member.values.len() <= env.read().unwrap().max_length
+    }
+
+    fn repair(member: &Numbers, env: Arc<RwLock<NumbersEnv>>) -> Option<Numbers> {
+        let max_length = env.read().unwrap().max_length;
+        if !env.read().unwrap().repairable || member.values.len() > max_length + 1 {
+            return None;
+        }
+        Some(Numbers { values: member.values[..max_length].to_vec() })
+    }
+
+    fn base(_: &mut NumbersEnv) -> Numbers {
+        Numbers { values: vec![0] }
+    }
+}
+
+
+
+/// the bigger the sum the better
+pub struct Sum;
+
+impl Problem<Numbers> for Sum {
+
+    fn empty() -> Self { Sum }
+
+    fn solve(&self, member: &mut Numbers) -> f32 {
+        member.values.iter().map(|x| *x as f32).sum()
+    }
+}
+
+
+
+fn population(max_length: usize, repairable: bool, crossover_rate: f32) -> Population<Numbers, NumbersEnv, Sum> {
+    Population::<Numbers, NumbersEnv, Sum>::new()
+        .size(50)
+        .constrain(NumbersEnv { max_length, repairable })
+        .populate_base()
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate,
+            distance: 1.0,
+            species_target: 5
+        })
+}
+
+
+
+#[test]
+fn invalid_children_are_repaired_or_discarded() {
+    let mut population = population(5, true, 0.5);
+    for _ in 0..30 {
+        population.train().unwrap();
+        assert!(population.members_mut().iter().all(|x| x.member.values.len() <= 5));
+    }
+    let repairs = population.history().iter().map(|x| x.repairs).sum::<usize>();
+    let discards = population.history().iter().map(|x| x.discards).sum::<usize>();
+    assert!(repairs > 0);
+    assert!(discards > 0);
+    assert!(population.members_mut().iter().any(|x| x.lineage.operators.contains(&String::from("repair"))));
+}
+
+
+
+#[test]
+fn children_which_cant_be_made_valid_are_clones() {
+    // the first members are as long as they can be and every child is one longer, with nothing to repair it
+    let mut population = population(1, false, 0.0);
+    population.train().unwrap();
+    assert!(population.members_mut().iter().all(|x| x.member.values.len() == 1));
+    let clones = population.members_mut()
+        .iter()
+        .filter(|x| x.lineage.operators == vec![String::from("clone")])
+        .count();
+    assert!(clones > 0);
+    // the counts show up in the stats of the generation the children were bred into once it's trained
+    assert_eq!(population.stats().unwrap().discards, 0);
+    population.train().unwrap();
+    let stats = population.stats().unwrap();
+    assert_eq!(stats.repairs, 0);
+    assert_eq!(stats.discards, clones * 10);
+}
+
+
+
+/// the behaviour of a list is how long it is
+pub struct Length;
+
+impl Behavior<Numbers> for Length {
+
+    fn empty() -> Self { Length }
+
+    fn evaluate(&self, member: &mut Numbers) -> (f32, Vec<f32>) {
+        (Sum.solve(member), vec![member.values.len() as f32])
+    }
+}
+
+
+
+#[test]
+fn map_elites_children_are_checked() {
+    let mut search = MapElites::<Numbers, NumbersEnv, Length>::new()
+        .constrain(NumbersEnv { max_length: 5, repairable: true })
+        .tessellation(Tessellation::grid(vec![10], vec![(0.0, 10.0)]))
+        .initial(20)
+        .batch(50)
+        .crossover_rate(0.5);
+    let (mut repairs, mut discards) = (0, 0);
+    for _ in 0..20 {
+        search.train().unwrap();
+        repairs += search.repairs();
+        discards += search.discards();
+    }
+    assert!(search.archive().elites.values().all(|x| x.member.values.len() <= 5));
+    assert!(repairs > 0);
+    assert!(discards > 0);
+}
+
+
+
+#[test]
+fn genomes_are_valid_by_default() {
+    let env = Arc::new(RwLock::new(RealVectorEnvironment::new()));
+    let member = RealVector::new(vec![1.0, 2.0]);
+    assert!(RealVector::validate(&member, Arc::clone(&env)));
+    assert!(RealVector::repair(&member, env).is_none());
+    let stats: GenerationStats = serde_json::from_str(
+        r#"{"generation":1,"size":2,"species":1,"best":1.0,"mean":0.5,"worst":0.0,"std_dev":0.5,"diversity":0.0}"#
+    ).unwrap();
+    assert_eq!((stats.repairs, stats.discards), (0, 0));
+}
@@ -0,0 +1,96 @@
+extern crate radiate_legacy;
+extern crate serde_json;
+
+use std::fs::{self, File};
+use std::sync::{Arc, RwLock};
+use radiate_legacy::prelude::*;
+
+
+
+/// one max
+pub struct OneMax;
+
+impl Problem<BitString> for OneMax {
+
+    fn empty() -> Self { OneMax }
+
+    fn solve(&self, member: &mut BitString) -> f32 {
+        member.ones() as f32
+    }
+}
+
+
+
+fn population() -> Population<BitString, BitStringEnvironment, OneMax> {
+    Population::<BitString, BitStringEnvironment, OneMax>::new()
+        .size(50)
+        .constrain(BitStringEnvironment::new()
+            .set_length(32)
+            .set_mutation_rate(0.03))
+        .configure(Config {
+            inbreed_rate: 0.001,
+            crossover_rate: 0.75,
+            distance: 0.3,
+            species_target: 5
+        })
+}
+
+
+
+/// the mean fitness over every generation of a run
+fn mean_fitness(history: &[GenerationStats]) -> f32 {
+    history.iter().map(|x| x.mean).sum::<f32>() / history.len() as f32
+}
+
+
+
+/// good members from an earlier run, each one is only missing a different block of four bits
+fn seeds() -> Vec<BitString> {
+    (0..3).map(|i| BitString::new((0..32).map(|bit| bit / 4 != i).collect())).collect()
+}
+
+
+
+#[test]
+fn variants_fill_the_population() {
+    let seeds = seeds();
+    let mut cold = population().populate_base();
+    let mut population = population().populate_variants(seeds.clone());
+    let members = (0..50).map(|i| population.member(i).unwrap().member.clone()).collect::<Vec<_>>();
+    assert!(population.member(50).is_none());
+    assert_eq!(&members[..3], &seeds[..]);
+    // variants are mutated copies of the seeds taken in turn
+    let env = Arc::new(RwLock::new(BitStringEnvironment::new().set_length(32)));
+    for (i, variant) in members.iter().enumerate().skip(3) {
+        assert!(BitString::distance(variant, &seeds[i % 3], Arc::clone(&env)) < 0.5);
+    }
+    assert!(members.iter().skip(3).any(|x| !seeds.contains(x)));
+    // starting from the seeds is well ahead of starting from scratch and stays ahead for a while
+    let warm = population.run(|_, fit, num| fit == 32.0 || num == 10).unwrap();
+    let cold = cold.run(|_, fit, num| fit == 32.0 || num == 10).unwrap();
+    assert!(warm.history[0].best >= 28.0 && warm.history[0].best > cold.history[0].best);
+    assert!(mean_fitness(&warm.history) > mean_fitness(&cold.history) + 3.0);
+}
+
+
+
+#[test]
+fn populate_from_saved_files() {
+    let dir = std::env::temp_dir().join("radiate_legacy_warm_start");
+    fs::create_dir_all(&dir).unwrap();
+    let seeds = seeds();
+    for (i, seed) in seeds.iter().enumerate() {
+        serde_json::to_writer(File::create(dir.join(format!("member_{}.json", i))).unwrap(), seed).unwrap();
+    }
+    fs::write(dir.join("notes.txt"), "not a member").unwrap();
+    let from_dir = population().populate_from_dir(dir.to_str().unwrap()).unwrap();
+    let loaded = (0..3).map(|i| from_dir.member(i).unwrap().member.clone()).collect::<Vec<_>>();
+    assert_eq!(loaded, seeds);
+    assert!(from_dir.member(49).is_some());
+    let path = dir.join("member_1.json");
+    let from_file = population().populate_from_files(&[path.to_str().unwrap()]).unwrap();
+    assert_eq!(from_file.member(0).unwrap().member, seeds[1]);
+    assert!(population().populate_from_files(&[]).is_err());
+    assert!(population().populate_from_files(&["does/not/exist.json"]).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -122,15 +122,26 @@ impl Evtree {
 
 impl Genome<Evtree, TreeEnvionment> for Evtree {
     /// one should be the more fit Evtree and two should be the less fit Evtree.
-    /// This function should attemp to produce a Evtree which is no higher than the 
-    /// specified max height of a Evtree.
+    /// The crossover consists of either subtreeing and crossing over trees 
+    /// or of mutating the structure of the tree by randomly mutating the neural network
+    /// in random nodes, or by adding nodes, gutting nodes, or shuffling the structure of the tree
     #[inline]
     fn crossover(one: &Evtree, two: &Evtree, settings: Arc<RwLock<TreeEnvionment>>, crossover_rate: f32) -> Option<Evtree> {
+        if rand::thread_rng().gen::<f32>() < crossover_rate {
+            Evtree::recombine(one, two, settings)
+        } else {
+            Evtree::mutate(one, settings)
+        }
+    }
+
+    /// Swap a biased random subtree of the more fit tree (one) with a biased random subtree of 
+    /// the less fit tree (two). This function should attemp to produce a Evtree which is no higher 
+    /// than the specified max height of a Evtree.
+    #[inline]
+    fn recombine(one: &Evtree, two: &Evtree, settings: Arc<RwLock<TreeEnvionment>>) -> Option<Evtree> {
         let set = &*(*settings).read().unwrap();
-        // make a complete copy of the more fit tree and declare a random 
-        // ThreadRng type to be used for random mutations
+        // make a complete copy of the more fit tree
         let mut result = one.clone();
-        let mut r = rand::thread_rng();
 
         // make sure that the tree that will be built will be less than the 
         // specified max height of a tree in a config type
@@ -141,29 +152,31 @@ impl Genome<Evtree, TreeEnvionment> for Evtree {
             node_two = two.get_biased_random_node();
         }
 
-        // The crossover consists of either subtreeing and crossing over trees 
-        // or of mutating the structure of the tree by randomly mutating the neural network
-        // in random nodes, or by adding nodes, gutting nodes, or shuffling the structure of the tree
-        if r.gen::<f32>() < crossover_rate {
-            let node_index = one.index_of(&node_one);
-            result.replace(node_index, node_two.deepcopy());
-        } else {
-            if r.gen::<f32>() < set.get_network_mutation_rate() {
-                result.edit_random_node_networks(set.weight_mutate_rate?, set.weight_transform_rate?, set.layer_mutate_rate?);
-            }
-            if r.gen::<f32>() < set.node_add_rate? {
-                result.insert_random(NetNode::new(set.input_size?, set.get_outputs()));
-            }
-            if r.gen::<f32>() < set.shuffle_rate? {
-                result.shuffle_tree(&mut r);
-            }
-            if r.gen::<f32>() < set.gut_rate? {
-                result.gut_random_node(&mut r);
-            }
-            result.update_size();
-        }
+        let node_index = one.index_of(&node_one);
+        result.replace(node_index, node_two.deepcopy());
+        Some(result)
+    }
 
-        // return the new tree
+    /// Mutate the structure of the tree by randomly mutating the neural network
+    /// in random nodes, or by adding nodes, gutting nodes, or shuffling the structure of the tree
+    #[inline]
+    fn mutate(member: &Evtree, settings: Arc<RwLock<TreeEnvionment>>) -> Option<Evtree> {
+        let set = &*(*settings).read().unwrap();
+        let mut result = member.clone();
+        let mut r = rand::thread_rng();
+        if r.gen::<f32>() < set.get_network_mutation_rate() {
+            result.edit_random_node_networks(set.weight_mutate_rate?, set.weight_transform_rate?, set.layer_mutate_rate?);
+        }
+        if r.gen::<f32>() < set.node_add_rate? {
+            result.insert_random(NetNode::new(set.input_size?, set.get_outputs()));
+        }
+        if r.gen::<f32>() < set.shuffle_rate? {
+            result.shuffle_tree(&mut r);
+        }
+        if r.gen::<f32>() < set.gut_rate? {
+            result.gut_random_node(&mut r);
+        }
+        result.update_size();
         Some(result)
     }
 
@@ -189,4 +202,35 @@ impl Genome<Evtree, TreeEnvionment> for Evtree {
         // return the abs value of the two tree's asymmetry
         (one.asymmetry() - two.asymmetry()).abs()
     }
+
+    /// the size of the tree is its number of nodes plus its height, so of two trees with the
+    /// same number of nodes the more balanced one is simpler. The nodes are counted from the
+    /// root because a recombined tree's size isn't updated
+    fn complexity(member: &Evtree) -> f32 {
+        let nodes = member.root_opt().map_or(0, |root| root.size());
+        (nodes + member.height()) as f32
+    }
+
+    /// A tree is valid if it is no taller than the max height. Recombination keeps to the max
+    /// height but inserting a random node during mutation can grow the tree past it. Without a
+    /// max height every tree is valid
+    fn validate(member: &Evtree, settings: Arc<RwLock<TreeEnvionment>>) -> bool {
+        match settings.read().unwrap().max_height {
+            Some(max_height) => member.height() <= max_height,
+            None => true
+        }
+    }
+
+    /// A tree which grew too tall is balanced, which keeps every node but makes the tree
+    /// as short as it can be. If it is still too tall it has too many nodes to be fixed
+    fn repair(member: &Evtree, settings: Arc<RwLock<TreeEnvionment>>) -> Option<Evtree> {
+        let mut result = member.clone();
+        result.balance();
+        result.update_size();
+        if result.height() <= settings.read().ok()?.max_height? {
+            Some(result)
+        } else {
+            None
+        }
+    }
 }